@@ -0,0 +1,132 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_derive::{Deserialize, Serialize};
+use serde_rusqlite::{LazyParams, SerializeOptions};
+
+const ROWS: usize = 1000;
+
+#[derive(Serialize, Deserialize)]
+struct Narrow {
+	id: i64,
+	name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Wide {
+	id: i64,
+	name: String,
+	description: Option<String>,
+	amount: f64,
+	count: u32,
+	flag: bool,
+	data: serde_bytes::ByteBuf,
+	c1: i64,
+	c2: i64,
+	c3: i64,
+	c4: f64,
+	c5: f64,
+	c6: String,
+	c7: String,
+	c8: Option<i64>,
+	c9: Option<f64>,
+}
+
+impl Wide {
+	fn new(id: i64) -> Self {
+		Self {
+			id,
+			name: format!("name {}", id),
+			description: (id % 2 == 0).then(|| format!("description {}", id)),
+			amount: id as f64 * 1.5,
+			count: id as u32,
+			flag: id % 3 == 0,
+			data: serde_bytes::ByteBuf::from(vec![id as u8; 64]),
+			c1: id,
+			c2: -id,
+			c3: id * 2,
+			c4: id as f64 / 3.,
+			c5: -(id as f64),
+			c6: "constant".into(),
+			c7: id.to_string(),
+			c8: Some(id),
+			c9: None,
+		}
+	}
+}
+
+const WIDE_COLUMNS: &str = "id, name, description, amount, count, flag, data, c1, c2, c3, c4, c5, c6, c7, c8, c9";
+const WIDE_PARAMS: &str = ":id, :name, :description, :amount, :count, :flag, :data, :c1, :c2, :c3, :c4, :c5, :c6, :c7, :c8, :c9";
+
+fn make_connection() -> rusqlite::Connection {
+	let con = rusqlite::Connection::open_in_memory().unwrap();
+	con.execute_batch(&format!(
+		"CREATE TABLE narrow(id, name); CREATE TABLE wide({});",
+		WIDE_COLUMNS
+	))
+	.unwrap();
+	for id in 0..ROWS as i64 {
+		con.execute(
+			"INSERT INTO narrow VALUES(:id, :name)",
+			serde_rusqlite::to_params_named(Narrow {
+				id,
+				name: format!("name {}", id),
+			})
+			.unwrap()
+			.to_slice()
+			.as_slice(),
+		)
+		.unwrap();
+		con.execute(
+			&format!("INSERT INTO wide VALUES({})", WIDE_PARAMS),
+			serde_rusqlite::to_params_named(Wide::new(id)).unwrap().to_slice().as_slice(),
+		)
+		.unwrap();
+	}
+	con
+}
+
+fn from_rows(c: &mut Criterion) {
+	let con = make_connection();
+	let mut group = c.benchmark_group("from_rows");
+	group.bench_function("narrow", |b| {
+		let mut stmt = con.prepare("SELECT * FROM narrow").unwrap();
+		b.iter(|| {
+			for row in serde_rusqlite::from_rows::<Narrow>(stmt.query([]).unwrap()) {
+				black_box(row.unwrap());
+			}
+		})
+	});
+	group.bench_function("wide", |b| {
+		let mut stmt = con.prepare("SELECT * FROM wide").unwrap();
+		b.iter(|| {
+			for row in serde_rusqlite::from_rows::<Wide>(stmt.query([]).unwrap()) {
+				black_box(row.unwrap());
+			}
+		})
+	});
+	group.bench_function("wide_vec", |b| {
+		let mut stmt = con.prepare("SELECT * FROM wide").unwrap();
+		b.iter(|| black_box(serde_rusqlite::from_rows_vec::<Wide>(stmt.query([]).unwrap(), ROWS).unwrap()))
+	});
+	group.finish();
+}
+
+fn to_params(c: &mut Criterion) {
+	let wide = Wide::new(42);
+	let tuple = (42, "name", Some(1.5), serde_bytes::Bytes::new(&[0; 64]), true);
+	let mut group = c.benchmark_group("to_params");
+	group.bench_function("positional", |b| {
+		b.iter(|| black_box(serde_rusqlite::to_params(black_box(&tuple)).unwrap()))
+	});
+	group.bench_function("positional_lazy", |b| {
+		b.iter(|| black_box(LazyParams::new(black_box(&tuple), SerializeOptions::default()).count()))
+	});
+	group.bench_function("named", |b| {
+		b.iter(|| black_box(serde_rusqlite::to_params_named(black_box(&wide)).unwrap()))
+	});
+	group.finish();
+}
+
+criterion_group!(benches, from_rows, to_params);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! Streaming of large `BLOB` values from a `std::io::Read`
+//!
+//! Serde needs the whole value in memory to serialize it, so instead of that the row is inserted with a zero-filled `BLOB`
+//! of the final size and then the data is copied into it using SQLite incremental blob I/O:
+//!
+//! ```
+//! use serde_derive::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct File {
+//!    name: String,
+//! }
+//!
+//! let connection = rusqlite::Connection::open_in_memory().unwrap();
+//! connection.execute_batch("CREATE TABLE file (name TEXT, data BLOB)").unwrap();
+//! let data = [1u8, 2, 3];
+//! let blob = serde_rusqlite::blob::blob_param_from_reader(&data[..], data.len()).unwrap();
+//! let mut params = serde_rusqlite::to_params_named(File { name: "file.bin".into() }).unwrap();
+//! params.push((":data".into(), blob.param()));
+//! connection.execute("INSERT INTO file (name, data) VALUES (:name, :data)", params.to_slice().as_slice()).unwrap();
+//! blob.write_into(&connection, "file", "data", connection.last_insert_rowid()).unwrap();
+//! let stored: Vec<u8> = connection.query_row("SELECT data FROM file", [], |row| row.get(0)).unwrap();
+//! assert_eq!(stored, data);
+//! ```
+
+use std::io::{self, Read};
+
+use rusqlite::blob::ZeroBlob;
+use rusqlite::types::ToSqlOutput;
+use rusqlite::{Connection, DatabaseName, ToSql};
+
+use crate::{Error, Result};
+
+/// `BLOB` argument that is bound as a zero-filled placeholder and is filled from the reader after the row is written
+///
+/// To get the instance call `blob_param_from_reader()`.
+pub struct BlobParam<R> {
+	reader: R,
+	len: i32,
+}
+
+impl<R: Read> BlobParam<R> {
+	/// Returns the placeholder argument for adding to `PositionalParams` or `NamedParamSlice`
+	///
+	/// `BlobParam` itself also implements `ToSql` so it can be bound directly too.
+	pub fn param(&self) -> Box<dyn ToSql> {
+		Box::new(ZeroBlob(self.len))
+	}
+
+	/// Copies the data from the reader into the `BLOB` stored in `column` of `table` row with `rowid`
+	///
+	/// The reader must produce at least the number of bytes passed to `blob_param_from_reader()`, the excess is not read.
+	pub fn write_into(self, connection: &Connection, table: &str, column: &str, rowid: i64) -> Result<()> {
+		let mut blob = connection.blob_open(DatabaseName::Main, table, column, rowid, false)?;
+		let expected = self.len as u64;
+		let written = io::copy(&mut self.reader.take(expected), &mut blob).map_err(|e| Error::Serialization(e.to_string()))?;
+		if written != expected {
+			return Err(Error::Serialization(format!(
+				"reader ended after {} bytes, expected {} bytes for the BLOB",
+				written, expected
+			)));
+		}
+		Ok(())
+	}
+}
+
+impl<R> ToSql for BlobParam<R> {
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+		Ok(ToSqlOutput::ZeroBlob(self.len))
+	}
+}
+
+/// Prepares `BLOB` argument of `len` bytes that are later streamed from `reader`
+///
+/// Fails with `Error::ValueTooLarge` if `len` exceeds the maximum size of `BLOB` that SQLite can bind (`i32::MAX`).
+pub fn blob_param_from_reader<R: Read>(reader: R, len: usize) -> Result<BlobParam<R>> {
+	let len = i32::try_from(len).map_err(|_| Error::ValueTooLarge(format!("BLOB of {} bytes is too large", len)))?;
+	Ok(BlobParam { reader, len })
+}
@@ -0,0 +1,309 @@
+//! Helpers to store `chrono` types
+//!
+//! Dates and times are stored using the `TEXT` formats or `REAL` Julian day numbers that SQLite date and time functions
+//! understand, durations are stored as `INTEGER`s or as ISO 8601 duration `TEXT`.
+//!
+//! Use them with `#[serde(with = "...")]` field attribute:
+//!
+//! ```
+//! use chrono::{NaiveDate, NaiveTime};
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::chrono::naive_date")]
+//!    date: NaiveDate,
+//!    #[serde(with = "serde_rusqlite::chrono::naive_time")]
+//!    time: NaiveTime,
+//! }
+//! ```
+
+use std::fmt;
+
+use ::chrono::format::ParseResult;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+fn parse_text<'de, D: Deserializer<'de>, T>(deserializer: D, parse: impl FnOnce(&str) -> ParseResult<T>) -> Result<T, D::Error> {
+	let text = String::deserialize(deserializer)?;
+	parse(&text).map_err(|e| de::Error::custom(format_args!("can't parse \"{}\": {}", text, e)))
+}
+
+fn serialize_display<S: Serializer>(value: impl fmt::Display, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.collect_str(&value)
+}
+
+/// Stores `chrono::NaiveDate` as `TEXT` in `YYYY-MM-DD` format
+pub mod naive_date {
+	use ::chrono::NaiveDate;
+	use serde::{Deserializer, Serializer};
+
+	const FORMAT: &str = "%Y-%m-%d";
+
+	pub fn serialize<S: Serializer>(value: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize_display(value.format(FORMAT), serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+		super::parse_text(deserializer, |text| NaiveDate::parse_from_str(text, FORMAT))
+	}
+}
+
+/// Stores `chrono::NaiveTime` as `TEXT` in `HH:MM:SS` format
+///
+/// Fractional seconds are added as `HH:MM:SS.SSS` only when they are not zero, 3, 6 or 9 digits are used depending on the
+/// precision of the value. `HH:MM` is also accepted during deserialization.
+pub mod naive_time {
+	use ::chrono::NaiveTime;
+	use serde::{Deserializer, Serializer};
+
+	const FORMAT: &str = "%H:%M:%S%.f";
+
+	pub fn serialize<S: Serializer>(value: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize_display(value.format(FORMAT), serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+		super::parse_text(deserializer, |text| {
+			NaiveTime::parse_from_str(text, FORMAT).or_else(|e| NaiveTime::parse_from_str(text, "%H:%M").map_err(|_| e))
+		})
+	}
+}
+
+/// Stores `chrono::NaiveDateTime` as `TEXT` in `YYYY-MM-DD HH:MM:SS` format
+///
+/// Fractional seconds are handled the same way as in [naive_time]. `T` separator between date and time is also accepted
+/// during deserialization.
+pub mod naive_datetime {
+	use ::chrono::NaiveDateTime;
+	use serde::{Deserializer, Serializer};
+
+	const FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+	pub fn serialize<S: Serializer>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize_display(value.format(FORMAT), serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+		super::parse_text(deserializer, |text| {
+			NaiveDateTime::parse_from_str(text, FORMAT)
+				.or_else(|e| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f").map_err(|_| e))
+		})
+	}
+}
+
+/// Stores `chrono::NaiveDateTime` as `REAL` Julian day number, the same way SQLite `julianday()` function does
+///
+/// The value is converted with millisecond precision like SQLite does internally, the sub-millisecond part is truncated
+/// during serialization and the stored number is rounded to the nearest millisecond during deserialization. `INTEGER`
+/// values are also accepted.
+pub mod naive_datetime_julian_day {
+	use ::chrono::{DateTime, NaiveDateTime};
+	use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+	/// Unix epoch as Julian day in milliseconds
+	const UNIX_EPOCH_MS: i64 = 210_866_760_000_000;
+	const MS_PER_DAY: f64 = 86_400_000.;
+
+	pub fn serialize<S: Serializer>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+		((value.and_utc().timestamp_millis() + UNIX_EPOCH_MS) as f64 / MS_PER_DAY).serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+		let value = f64::deserialize(deserializer)?;
+		let julian_ms = value * MS_PER_DAY + 0.5;
+		// the bounds keep the cast lossless and the subtraction from overflowing, exact range is checked by chrono
+		if !(0. ..=i64::MAX as f64 / 2.).contains(&julian_ms) {
+			return Err(de::Error::custom(format_args!(
+				"Julian day {} is out of range for NaiveDateTime",
+				value
+			)));
+		}
+		DateTime::from_timestamp_millis(julian_ms as i64 - UNIX_EPOCH_MS)
+			.map(|value| value.naive_utc())
+			.ok_or_else(|| de::Error::custom(format_args!("Julian day {} is out of range for NaiveDateTime", value)))
+	}
+}
+
+/// Stores `chrono::DateTime<FixedOffset>` as `TEXT` in RFC 3339 format, e.g. `2024-03-01T12:30:00+05:30`
+///
+/// The offset is kept as is (including `+00:00`) instead of being normalized to UTC, so the value round-trips with the
+/// original offset. Fractional seconds are handled the same way as in [naive_time]. Space separator between date and time
+/// and `Z` for the zero offset are also accepted during deserialization. Note that SQLite date and time functions convert
+/// such values to UTC.
+pub mod date_time_fixed_offset {
+	use ::chrono::{DateTime, FixedOffset, SecondsFormat};
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &DateTime<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&value.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error> {
+		super::parse_text(deserializer, DateTime::parse_from_rfc3339)
+	}
+}
+
+fn time_delta_from_int<'de, D: Deserializer<'de>>(
+	deserializer: D,
+	unit: &str,
+	from_int: impl FnOnce(i64) -> Option<::chrono::TimeDelta>,
+) -> Result<::chrono::TimeDelta, D::Error> {
+	let value = i64::deserialize(deserializer)?;
+	from_int(value).ok_or_else(|| de::Error::custom(format_args!("{} {} is out of range for TimeDelta", value, unit)))
+}
+
+/// Stores `chrono::TimeDelta` (also known as `chrono::Duration`) as `INTEGER` number of whole seconds
+///
+/// The fractional part of the second is truncated during serialization. The whole range of `TimeDelta` fits into `i64`
+/// seconds, but the values larger than about 292 million years in either direction can't be deserialized back and produce
+/// an error.
+pub mod time_delta_seconds {
+	use ::chrono::TimeDelta;
+	use serde::{Deserializer, Serialize, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+		value.num_seconds().serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+		super::time_delta_from_int(deserializer, "seconds", TimeDelta::try_seconds)
+	}
+}
+
+/// Stores `chrono::TimeDelta` (also known as `chrono::Duration`) as `INTEGER` number of milliseconds
+///
+/// The sub-millisecond part is truncated during serialization. `TimeDelta` is limited to `i64` milliseconds so all of its
+/// values round-trip, only the stored `i64::MIN` is out of range and produces an error during deserialization.
+pub mod time_delta_millis {
+	use ::chrono::TimeDelta;
+	use serde::{Deserializer, Serialize, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+		value.num_milliseconds().serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+		super::time_delta_from_int(deserializer, "milliseconds", TimeDelta::try_milliseconds)
+	}
+}
+
+/// Stores `chrono::TimeDelta` (also known as `chrono::Duration`) as `TEXT` in ISO 8601 duration format, e.g. `PT1H30M`
+///
+/// Only the exact units are used: days (`D`, always 24 hours), hours, minutes and seconds with up to 9 fractional digits.
+/// Weeks (`P2W`) are also accepted during deserialization, years and months are rejected because their length is not
+/// fixed. Negative durations are prefixed with `-`, e.g. `-PT5S`, zero duration is `PT0S`.
+pub mod time_delta_iso8601 {
+	use std::fmt::Write;
+
+	use ::chrono::TimeDelta;
+	use serde::{de, Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format(value))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+		let text = String::deserialize(deserializer)?;
+		parse(&text).ok_or_else(|| de::Error::custom(format_args!("can't parse \"{}\" as ISO 8601 duration", text)))
+	}
+
+	fn format(value: &TimeDelta) -> String {
+		let abs = value.abs();
+		let secs = abs.num_seconds();
+		let nanos = abs.subsec_nanos();
+		let (days, hours, minutes, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+		let mut out = String::from(if value < &TimeDelta::zero() {
+			"-P"
+		} else {
+			"P"
+		});
+		if days > 0 {
+			write!(out, "{}D", days).unwrap();
+		}
+		if hours > 0 || minutes > 0 || secs > 0 || nanos > 0 || days == 0 {
+			out.push('T');
+			if hours > 0 {
+				write!(out, "{}H", hours).unwrap();
+			}
+			if minutes > 0 {
+				write!(out, "{}M", minutes).unwrap();
+			}
+			if secs > 0 || nanos > 0 || (hours == 0 && minutes == 0) {
+				write!(out, "{}", secs).unwrap();
+				if nanos > 0 {
+					let frac = format!("{:09}", nanos);
+					write!(out, ".{}", frac.trim_end_matches('0')).unwrap();
+				}
+				out.push('S');
+			}
+		}
+		out
+	}
+
+	fn parse(text: &str) -> Option<TimeDelta> {
+		let (negative, text) = match text.strip_prefix('-') {
+			Some(text) => (true, text),
+			None => (false, text.strip_prefix('+').unwrap_or(text)),
+		};
+		let text = text.strip_prefix('P')?;
+		let (date, time) = match text.split_once('T') {
+			Some((_, "")) => return None,
+			Some((date, time)) => (date, Some(time)),
+			None => (text, None),
+		};
+		if date.is_empty() && time.is_none() {
+			return None;
+		}
+		let mut out = TimeDelta::zero();
+		for (value, unit) in components(date)? {
+			let unit_secs = match unit {
+				'W' => 7 * 86400,
+				'D' => 86400,
+				_ => return None,
+			};
+			out = out.checked_add(&TimeDelta::try_seconds(value.parse::<i64>().ok()?.checked_mul(unit_secs)?)?)?;
+		}
+		for (value, unit) in components(time.unwrap_or(""))? {
+			let part = match unit {
+				'H' => TimeDelta::try_seconds(value.parse::<i64>().ok()?.checked_mul(3600)?)?,
+				'M' => TimeDelta::try_seconds(value.parse::<i64>().ok()?.checked_mul(60)?)?,
+				'S' => {
+					let (secs, frac) = value.split_once('.').unwrap_or((value, ""));
+					if frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+						return None;
+					}
+					let nanos = if frac.is_empty() {
+						0
+					} else {
+						format!("{:0<9}", frac).parse().ok()?
+					};
+					TimeDelta::try_seconds(secs.parse().ok()?)?.checked_add(&TimeDelta::nanoseconds(nanos))?
+				}
+				_ => return None,
+			};
+			out = out.checked_add(&part)?;
+		}
+		Some(if negative {
+			-out
+		} else {
+			out
+		})
+	}
+
+	/// Splits e.g. `1H30M` into `[("1", 'H'), ("30", 'M')]` checking that the units don't repeat
+	fn components(text: &str) -> Option<Vec<(&str, char)>> {
+		let mut out: Vec<(&str, char)> = vec![];
+		let mut rest = text;
+		while !rest.is_empty() {
+			let unit_pos = rest.find(|c: char| c.is_ascii_alphabetic())?;
+			let unit = rest[unit_pos..].chars().next()?;
+			let value = &rest[..unit_pos];
+			if value.is_empty() || value.starts_with(['+', '-']) || out.iter().any(|(_, seen)| *seen == unit) {
+				return None;
+			}
+			out.push((value, unit));
+			rest = &rest[unit_pos + 1..];
+		}
+		Some(out)
+	}
+}
@@ -0,0 +1,138 @@
+use rusqlite::types::Value;
+use serde::de::{DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use super::{add_field_to_error, DeserializeOptions, RowSource, RowValue};
+use crate::{Error, Result};
+
+/// Deserializer for the values of all rows collected column by column
+///
+/// Each column is presented as a map entry with a sequence of the values from every row, so the target type is expected to
+/// be a `struct` (or a map) of collections.
+pub struct ColumnarDeserializer<'cols> {
+	columns: &'cols [String],
+	values: &'cols [Vec<Value>],
+	options: DeserializeOptions,
+}
+
+impl<'cols> ColumnarDeserializer<'cols> {
+	/// `values` must contain a `Vec` of the row values for each of the `columns`
+	pub fn new(columns: &'cols [String], values: &'cols [Vec<Value>], options: DeserializeOptions) -> Self {
+		Self {
+			columns,
+			values,
+			options,
+		}
+	}
+}
+
+impl<'de> Deserializer<'de> for ColumnarDeserializer<'de> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+		Err(Error::de_unsupported("columnar layout other than struct or map"))
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_map(ColumnarMapAccess { idx: 0, de: self })
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value> {
+		self.deserialize_map(visitor)
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+		newtype_struct seq tuple tuple_struct enum identifier ignored_any
+	}
+}
+
+struct ColumnarMapAccess<'cols> {
+	idx: usize,
+	de: ColumnarDeserializer<'cols>,
+}
+
+impl<'de> MapAccess<'de> for ColumnarMapAccess<'de> {
+	type Error = Error;
+
+	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+		match self.de.columns.get(self.idx) {
+			Some(column) => seed
+				.deserialize(column.as_str().into_deserializer())
+				.map(Some)
+				.map_err(|e| add_field_to_error(e, column)),
+			None => Ok(None),
+		}
+	}
+
+	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+		let column = &self.de.columns[self.idx];
+		let out = seed
+			.deserialize(ColumnDeserializer {
+				idx: self.idx,
+				name: column,
+				values: &self.de.values[self.idx],
+				options: self.de.options,
+			})
+			.map_err(|e| add_field_to_error(e, column));
+		self.idx += 1;
+		out
+	}
+}
+
+/// Deserializer for the sequence of values of a single column
+struct ColumnDeserializer<'cols> {
+	idx: usize,
+	name: &'cols str,
+	values: &'cols [Value],
+	options: DeserializeOptions,
+}
+
+impl<'de> Deserializer<'de> for ColumnDeserializer<'de> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(ColumnSeqAccess { row: 0, de: self })
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+		newtype_struct seq tuple tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+struct ColumnSeqAccess<'cols> {
+	row: usize,
+	de: ColumnDeserializer<'cols>,
+}
+
+impl<'de> SeqAccess<'de> for ColumnSeqAccess<'de> {
+	type Error = Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+		if self.row >= self.de.values.len() {
+			return Ok(None);
+		}
+		let out = seed.deserialize(RowValue {
+			idx: self.row,
+			row: RowSource::Column {
+				idx: self.de.idx,
+				name: self.de.name,
+				values: self.de.values,
+			},
+			options: self.de.options,
+			ignored: None,
+		});
+		self.row += 1;
+		out.map(Some)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.de.values.len() - self.row)
+	}
+}
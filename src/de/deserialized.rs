@@ -0,0 +1,48 @@
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, Value, ValueRef};
+use serde::de::DeserializeOwned;
+
+use super::{DeserializeOptions, RowSource, RowValue};
+
+/// Wrapper that reads any `T: serde::Deserialize` from a single column
+///
+/// Allows using serde for one column while staying within rusqlite API for the rest of the row:
+///
+/// ```
+/// use serde_derive::Deserialize;
+/// use serde_rusqlite::Deserialized;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// enum Status {
+///    Active,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let (Deserialized(status), id) = connection
+///    .query_row("SELECT 'Active' AS status, 42 AS id", [], |row| {
+///       Ok((row.get::<_, Deserialized<Status>>("status")?, row.get::<_, i64>("id")?))
+///    })
+///    .unwrap();
+/// assert_eq!((status, id), (Status::Active, 42));
+/// ```
+///
+/// `T` is deserialized the same way as a field of a `struct` with the default `DeserializeOptions`, the errors are
+/// reported by rusqlite as `FromSqlConversionFailure`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Deserialized<T>(pub T);
+
+impl<T: DeserializeOwned> FromSql for Deserialized<T> {
+	fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+		let values = [Value::from(value)];
+		let value = RowValue {
+			idx: 0,
+			row: RowSource::Column {
+				idx: 0,
+				name: "",
+				values: &values,
+			},
+			options: DeserializeOptions::default(),
+			ignored: None,
+		};
+		T::deserialize(value).map(Self).map_err(|e| FromSqlError::Other(Box::new(e)))
+	}
+}
@@ -3,12 +3,14 @@ use std::marker::PhantomData;
 use rusqlite::{Row, Rows};
 use serde::de::DeserializeOwned;
 
-use crate::{Error, Result};
+use crate::{DeserializeOptions, Error, Result, RowDeserializer};
 
 /// Iterator to automatically deserialize each row from owned `rusqlite::Rows` into `D: serde::Deserialize`
 pub struct DeserRows<'stmt, D> {
 	rows: Rows<'stmt>,
 	columns: Option<Vec<String>>,
+	options: DeserializeOptions,
+	row_index: usize,
 	d: PhantomData<*const D>,
 }
 
@@ -17,16 +19,37 @@ impl<'stmt, D: DeserializeOwned> DeserRows<'stmt, D> {
 		Self {
 			columns: columns_from_rows(&rows),
 			rows,
+			options: DeserializeOptions::default(),
+			row_index: 0,
 			d: PhantomData,
 		}
 	}
+
+	/// Deserializes the remaining rows with `options` instead of the default ones
+	pub fn with_options(mut self, options: DeserializeOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Returns the underlying `rusqlite::Rows` positioned after the last deserialized row
+	///
+	/// The column names are taken from the statement, so wrapping the returned `Rows` into `DeserRows` again continues the
+	/// deserialization with the same columns.
+	pub fn into_rows(self) -> Rows<'stmt> {
+		self.rows
+	}
+
+	/// Returns the underlying `rusqlite::Rows` to read some of the remaining rows without deserialization
+	pub fn rows_mut(&mut self) -> &mut Rows<'stmt> {
+		&mut self.rows
+	}
 }
 
 impl<D: DeserializeOwned> Iterator for DeserRows<'_, D> {
 	type Item = Result<D>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		deser_row(self.rows.next(), &self.columns)
+		deser_row(self.rows.next(), &self.columns, &self.options, &mut self.row_index)
 	}
 }
 
@@ -34,6 +57,8 @@ impl<D: DeserializeOwned> Iterator for DeserRows<'_, D> {
 pub struct DeserRowsRef<'rows, 'stmt, D> {
 	rows: &'rows mut Rows<'stmt>,
 	columns: Option<Vec<String>>,
+	options: DeserializeOptions,
+	row_index: usize,
 	d: PhantomData<*const D>,
 }
 
@@ -42,24 +67,165 @@ impl<'rows, 'stmt, D: DeserializeOwned> DeserRowsRef<'rows, 'stmt, D> {
 		Self {
 			columns: columns_from_rows(rows),
 			rows,
+			options: DeserializeOptions::default(),
+			row_index: 0,
 			d: PhantomData,
 		}
 	}
+
+	/// Deserializes the remaining rows with `options` instead of the default ones
+	pub fn with_options(mut self, options: DeserializeOptions) -> Self {
+		self.options = options;
+		self
+	}
 }
 
 impl<D: DeserializeOwned> Iterator for DeserRowsRef<'_, '_, D> {
 	type Item = Result<D>;
 
 	fn next(&mut self) -> Option<Self::Item> {
-		deser_row(self.rows.next(), &self.columns)
+		deser_row(self.rows.next(), &self.columns, &self.options, &mut self.row_index)
+	}
+}
+
+/// Iterator to automatically deserialize rows from owned `rusqlite::Rows` into `D: serde::Deserialize` skipping rows that
+/// don't match a predicate
+pub struct DeserRowsFiltered<'stmt, D, F> {
+	rows: Rows<'stmt>,
+	columns: Option<Vec<String>>,
+	options: DeserializeOptions,
+	row_index: usize,
+	predicate: F,
+	d: PhantomData<*const D>,
+}
+
+impl<'stmt, D: DeserializeOwned, F: FnMut(&Row) -> bool> DeserRowsFiltered<'stmt, D, F> {
+	pub fn new(rows: Rows<'stmt>, predicate: F) -> Self {
+		Self {
+			columns: columns_from_rows(&rows),
+			rows,
+			options: DeserializeOptions::default(),
+			row_index: 0,
+			predicate,
+			d: PhantomData,
+		}
+	}
+
+	/// Deserializes the remaining rows with `options` instead of the default ones
+	pub fn with_options(mut self, options: DeserializeOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+impl<D: DeserializeOwned, F: FnMut(&Row) -> bool> Iterator for DeserRowsFiltered<'_, D, F> {
+	type Item = Result<D>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let row = self.rows.next();
+			if let Ok(Some(row)) = row {
+				if !(self.predicate)(row) {
+					self.row_index += 1;
+					continue;
+				}
+			}
+			return deser_row(row, &self.columns, &self.options, &mut self.row_index);
+		}
 	}
 }
 
+/// Iterator to automatically deserialize each row from owned `rusqlite::Rows` into `D: serde::Deserialize` together with
+/// the row's `rowid`
+///
+/// Check the crate's `from_rows_with_rowid()` function for the details on how the `rowid` column is located.
+pub struct DeserRowsWithRowid<'stmt, D> {
+	rows: Rows<'stmt>,
+	columns: Option<Vec<String>>,
+	offset: usize,
+	options: DeserializeOptions,
+	row_index: usize,
+	d: PhantomData<*const D>,
+}
+
+impl<'stmt, D: DeserializeOwned> DeserRowsWithRowid<'stmt, D> {
+	pub fn new(rows: Rows<'stmt>) -> Self {
+		let mut columns = columns_from_rows(&rows);
+		let mut offset = 0;
+		if let Some(columns) = &mut columns {
+			if columns.first().is_some_and(|first| is_rowid_column(first)) {
+				columns.remove(0);
+				offset = 1;
+			}
+		}
+		Self {
+			rows,
+			columns,
+			offset,
+			options: DeserializeOptions::default(),
+			row_index: 0,
+			d: PhantomData,
+		}
+	}
+
+	/// Deserializes the remaining rows with `options` instead of the default ones
+	pub fn with_options(mut self, options: DeserializeOptions) -> Self {
+		self.options = options;
+		self
+	}
+}
+
+impl<D: DeserializeOwned> Iterator for DeserRowsWithRowid<'_, D> {
+	type Item = Result<(i64, D)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(columns) = &self.columns {
+			match self.rows.next() {
+				Ok(Some(row)) => {
+					let index = self.row_index;
+					self.row_index += 1;
+					Some(
+						row.get::<_, i64>(0)
+							.map_err(Error::from)
+							.and_then(|rowid| {
+								D::deserialize(
+									RowDeserializer::from_row_with_options(row, columns, self.options).with_offset(self.offset),
+								)
+								.map(|d| (rowid, d))
+							})
+							.map_err(|e| add_row_to_error(e, index)),
+					)
+				}
+				Ok(None) => None,
+				Err(e) => Some(Err(e.into())),
+			}
+		} else {
+			Some(Err(Error::ColumnNamesNotAvailable))
+		}
+	}
+}
+
+fn is_rowid_column(name: &str) -> bool {
+	["rowid", "oid", "_rowid_"]
+		.iter()
+		.any(|alias| name.eq_ignore_ascii_case(alias))
+}
+
 #[inline]
-fn deser_row<D: DeserializeOwned>(row: rusqlite::Result<Option<&Row>>, columns: &Option<Vec<String>>) -> Option<Result<D>> {
+/// Deserializes the `row`, `row_index` is the index of that row and is incremented for the next one
+fn deser_row<D: DeserializeOwned>(
+	row: rusqlite::Result<Option<&Row>>,
+	columns: &Option<Vec<String>>,
+	options: &DeserializeOptions,
+	row_index: &mut usize,
+) -> Option<Result<D>> {
 	if let Some(columns) = columns {
 		match row {
-			Ok(Some(row)) => Some(crate::from_row_with_columns(row, columns)),
+			Ok(Some(row)) => {
+				let index = *row_index;
+				*row_index += 1;
+				Some(crate::from_row_with_options(row, columns, options).map_err(|e| add_row_to_error(e, index)))
+			}
 			Ok(None) => None,
 			Err(e) => Some(Err(e.into())),
 		}
@@ -68,6 +234,24 @@ fn deser_row<D: DeserializeOwned>(row: rusqlite::Result<Option<&Row>>, columns:
 	}
 }
 
+/// Fills the `row` field of the `error` or wraps it into `Error::InRow` if it doesn't have one
+fn add_row_to_error(mut error: Error, index: usize) -> Error {
+	match &mut error {
+		Error::Deserialization { row, .. }
+		| Error::TypeMismatch { row, .. }
+		| Error::UnexpectedNull { row, .. }
+		| Error::ColumnCountMismatch { row, .. } => {
+			*row = Some(index);
+			error
+		}
+		Error::InRow { .. } => error,
+		_ => Error::InRow {
+			row: index,
+			source: Box::new(error),
+		},
+	}
+}
+
 fn columns_from_rows(rows: &rusqlite::Rows) -> Option<Vec<String>> {
 	rows.as_ref().map(|stmt| {
 		let len = stmt.column_count();
@@ -0,0 +1,122 @@
+use std::iter;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use crate::{Error, Result};
+
+macro_rules! forward_to_method {
+	($($($fun:ident)* => $target:ident;)*) => {
+		$($(
+			fn $fun<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				self.$target(visitor)
+			}
+		)*)*
+	}
+}
+
+/// Deserializer for a struct field that has no corresponding column
+///
+/// Produces the same values that `Default::default()` returns for the standard types: zeroes, `false`, empty strings and
+/// collections, `None` for `Option`s. Nested structs and tuples get all of their fields set in the same manner.
+#[derive(Clone, Copy)]
+pub struct MissingValue;
+
+impl<'de> IntoDeserializer<'de, Error> for MissingValue {
+	type Deserializer = Self;
+
+	fn into_deserializer(self) -> Self::Deserializer {
+		self
+	}
+}
+
+impl<'de> Deserializer<'de> for MissingValue {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_bool(false)
+	}
+
+	fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_i64(0)
+	}
+
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_u64(0)
+	}
+
+	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_f64(0.)
+	}
+
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_char('\0')
+	}
+
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_str("")
+	}
+
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_bytes(&[])
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_none()
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(SeqDeserializer::new(iter::empty::<Self>()))
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(SeqDeserializer::new(iter::repeat_n(self, len)))
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value> {
+		self.deserialize_tuple(len, visitor)
+	}
+
+	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_map(MapDeserializer::new(iter::empty::<(Self, Self)>()))
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value> {
+		visitor.visit_map(MapDeserializer::new(fields.iter().map(|field| (*field, self))))
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		_visitor: V,
+	) -> Result<V::Value> {
+		Err(de::Error::custom("can't provide a default value for enum"))
+	}
+
+	forward_to_method! {
+		deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i128 => deserialize_i64;
+		deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u128 => deserialize_u64;
+		deserialize_f32 => deserialize_f64;
+		deserialize_string => deserialize_str;
+		deserialize_byte_buf => deserialize_bytes;
+	}
+
+	forward_to_deserialize_any! {
+		unit unit_struct identifier ignored_any
+	}
+}
@@ -1,15 +1,72 @@
-use std::{f32, f64};
+use std::cell::RefCell;
+use std::str::FromStr;
+use std::{any, f32, f64, fmt};
 
-use rusqlite::types::{FromSql, Value};
+use rusqlite::types::{FromSql, Type, Value, ValueRef};
 use rusqlite::Row;
-use serde::de::{DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
 use serde::{forward_to_deserialize_any, Deserializer};
 
-pub use iter::{DeserRows, DeserRowsRef};
+pub use deserialized::Deserialized;
+pub use iter::{DeserRows, DeserRowsFiltered, DeserRowsRef, DeserRowsWithRowid};
+pub use options::{BlobRepresentation, DeserializeOptions};
+pub use raw_row::{RawRow, RAW_ROW_NEWTYPE};
 
+use crate::ser::{encode_raw_value, RAW_VALUE_NEWTYPE};
 use crate::{Error, Result};
+pub(crate) use columnar::ColumnarDeserializer;
+use missing::MissingValue;
+pub(crate) use packed::PackedDeserializer;
+use source::RowSource;
 
+mod columnar;
+mod deserialized;
 mod iter;
+mod missing;
+mod options;
+mod packed;
+mod raw_row;
+mod source;
+
+macro_rules! deserialize_integer {
+	($($fun:ident)*) => {
+		$(
+			fn $fun<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				match self.value()? {
+					Value::Real(_) if self.options.strict_numeric => Err(Error::type_mismatch("INTEGER", Type::Real)),
+					Value::Real(val) if self.options.integer_from_real => visitor.visit_i64(real_to_integer(val)?),
+					Value::Text(val) if self.options.numeric_from_text => visitor.visit_i64(parse_text(&val)?),
+					val => self.deserialize_not_null_helper(visitor, val),
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! deserialize_not_null {
+	($($fun:ident)*) => {
+		$(
+			fn $fun<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				let val = self.value()?;
+				self.deserialize_not_null_helper(visitor, val)
+			}
+		)*
+	}
+}
+
+#[cfg(feature = "serde_json")]
+macro_rules! deserialize_json_text {
+	($($fun:ident($($arg:ident: $typ:ty),*))*) => {
+		$(
+			fn $fun<V: Visitor<'de>>(self, $($arg: $typ,)* visitor: V) -> Result<V::Value> {
+				match self.value()? {
+					Value::Text(val) => parse_json(&val)?.$fun($($arg,)* visitor).map_err(de::Error::custom),
+					val => self.deserialize_any_helper(visitor, val),
+				}
+			}
+		)*
+	}
+}
 
 macro_rules! forward_to_row_value_deserializer {
 	($($fun:ident)*) => {
@@ -25,17 +82,170 @@ macro_rules! forward_to_row_value_deserializer {
 ///
 /// You shouldn't use it directly, but via the crate's `from_row()` function. Check the crate documentation for example.
 pub struct RowDeserializer<'row, 'stmt, 'cols> {
-	row: &'row Row<'stmt>,
+	row: RowSource<'row, 'stmt>,
 	columns: &'cols [String],
+	offset: usize,
+	options: DeserializeOptions,
+	/// Use column indices instead of the names as the keys for maps
+	index_keys: bool,
+	/// Receives the indices of the columns that the target type ignored
+	ignored: Option<&'row RefCell<Vec<usize>>>,
+	/// Columns that are not presented to `struct`s and maps at all
+	skip_columns: &'cols [&'cols str],
 }
 
 impl<'row, 'stmt, 'cols> RowDeserializer<'row, 'stmt, 'cols> {
 	pub fn from_row_with_columns(row: &'row Row<'stmt>, columns: &'cols [String]) -> Self {
-		Self { row, columns }
+		Self::from_row_with_options(row, columns, DeserializeOptions::default())
+	}
+
+	pub fn from_row_with_options(row: &'row Row<'stmt>, columns: &'cols [String], options: DeserializeOptions) -> Self {
+		Self {
+			row: RowSource::Row(row),
+			columns,
+			offset: 0,
+			options,
+			index_keys: false,
+			ignored: None,
+			skip_columns: &[],
+		}
+	}
+
+	/// Creates deserializer for the row assembled in memory, `columns` must contain the names of the `values` in order
+	pub(crate) fn from_values_with_options(
+		values: &'row [(String, Value)],
+		columns: &'cols [String],
+		options: DeserializeOptions,
+	) -> Self {
+		Self {
+			row: RowSource::Values(values),
+			columns,
+			offset: 0,
+			options,
+			index_keys: false,
+			ignored: None,
+			skip_columns: &[],
+		}
+	}
+
+	/// Returns the storage class of the value in the column that the deserializer is positioned at without consuming it
+	///
+	/// Useful when the target type depends on the stored value, e.g. to read an id that can be stored both as `INTEGER` and
+	/// `TEXT` into an `enum`. Note that it can only be called from the hand-written code that creates the `RowDeserializer`
+	/// itself, like the closure passed to `query_and_then()`. `Deserialize` implementations receive a generic `Deserializer`
+	/// and can't reach it:
+	///
+	/// ```
+	/// use rusqlite::types::Type;
+	/// use serde::Deserialize;
+	/// use serde_rusqlite::RowDeserializer;
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// enum Id {
+	///    Number(i64),
+	///    Name(String),
+	/// }
+	///
+	/// fn id_from_row(row: &rusqlite::Row) -> serde_rusqlite::Result<Id> {
+	///    let de = RowDeserializer::from_row_with_columns(row, &[]);
+	///    match de.peek_storage_class()? {
+	///       Type::Integer => i64::deserialize(de).map(Id::Number),
+	///       _ => String::deserialize(de).map(Id::Name),
+	///    }
+	/// }
+	///
+	/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+	/// let mut stmt = connection.prepare("SELECT 1 UNION ALL SELECT 'two'").unwrap();
+	/// let ids = stmt
+	///    .query_and_then([], id_from_row)
+	///    .unwrap()
+	///    .collect::<Result<Vec<_>, _>>()
+	///    .unwrap();
+	/// assert_eq!(ids, [Id::Number(1), Id::Name("two".into())]);
+	/// ```
+	///
+	/// Inside a `Deserialize` implementation (e.g. for a field with `#[serde(deserialize_with)]`) read the value into
+	/// `ser::Raw` instead and match on the `rusqlite::types::Value` it holds:
+	///
+	/// ```
+	/// use rusqlite::types::Value;
+	/// use serde::{de, Deserialize as _, Deserializer};
+	/// use serde_derive::Deserialize;
+	/// use serde_rusqlite::ser::Raw;
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// enum Id {
+	///    Number(i64),
+	///    Name(String),
+	/// }
+	///
+	/// fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Id, D::Error> {
+	///    match Raw::deserialize(deserializer)?.0 {
+	///       Value::Integer(id) => Ok(Id::Number(id)),
+	///       Value::Text(id) => Ok(Id::Name(id)),
+	///       _ => Err(de::Error::custom("expected INTEGER or TEXT id")),
+	///    }
+	/// }
+	///
+	/// #[derive(Deserialize)]
+	/// struct Example {
+	///    #[serde(deserialize_with = "deserialize_id")]
+	///    id: Id,
+	/// }
+	///
+	/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+	/// let example = connection
+	///    .query_row("SELECT 'two' AS id", [], |row| Ok(serde_rusqlite::from_row::<Example>(row).unwrap()))
+	///    .unwrap();
+	/// assert_eq!(example.id, Id::Name("two".into()));
+	/// ```
+	pub fn peek_storage_class(&self) -> Result<Type> {
+		Ok(self.row.get_ref(self.offset)?.data_type())
+	}
+
+	/// Makes `columns` refer to the row columns starting at `offset` instead of the first one
+	pub(crate) fn with_offset(mut self, offset: usize) -> Self {
+		self.offset = offset;
+		self
+	}
+
+	/// Makes maps receive column indices (starting at 0) as the keys instead of the column names
+	pub(crate) fn with_index_keys(mut self) -> Self {
+		self.index_keys = true;
+		self
+	}
+
+	/// Makes `struct`s and maps skip the `skip_columns` as if they were not present in the row
+	pub(crate) fn with_skip_columns(mut self, skip_columns: &'cols [&'cols str]) -> Self {
+		self.skip_columns = skip_columns;
+		self
+	}
+
+	/// Records the indices of the columns that the target type didn't consume (e.g. unknown `struct` fields) in `ignored`
+	pub(crate) fn with_ignored(mut self, ignored: &'row RefCell<Vec<usize>>) -> Self {
+		self.ignored = Some(ignored);
+		self
 	}
 
 	fn row_value(&self) -> RowValue<'row, 'stmt> {
-		RowValue { row: self.row, idx: 0 }
+		self.row_value_at(0)
+	}
+
+	fn column_matches(&self, column: &str, field: &str) -> bool {
+		if self.options.case_insensitive_columns {
+			column.eq_ignore_ascii_case(field)
+		} else {
+			column == field
+		}
+	}
+
+	fn row_value_at(&self, idx: usize) -> RowValue<'row, 'stmt> {
+		RowValue {
+			row: self.row,
+			idx: self.offset + idx,
+			options: self.options,
+			ignored: self.ignored,
+		}
 	}
 }
 
@@ -46,25 +256,56 @@ impl<'de> Deserializer<'de> for RowDeserializer<'de, '_, '_> {
 		self.row_value().deserialize_unit_struct(name, visitor)
 	}
 
-	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
-		visitor.visit_newtype_struct(self.row_value())
+	/// The whole row is passed to the inner type, so the wrappers of both single values and `struct`s work
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+		if name == RAW_ROW_NEWTYPE {
+			return visitor.visit_byte_buf(self.row.encode_row(None)?.0);
+		}
+		if name == RAW_VALUE_NEWTYPE {
+			return self.row_value().deserialize_newtype_struct(name, visitor);
+		}
+		visitor.visit_newtype_struct(self)
 	}
 
-	fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
-		visitor.visit_seq(RowSeqAccess { idx: 0, de: self })
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(RowSeqAccess { idx: 0, len, de: self })
 	}
 
 	fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-		visitor.visit_map(RowMapAccess { idx: 0, de: self })
+		visitor.visit_map(RowMapAccess {
+			idx: 0,
+			de: self,
+			fields: &[],
+			missing_fields: vec![],
+		})
 	}
 
 	fn deserialize_struct<V: Visitor<'de>>(
 		self,
 		_name: &'static str,
-		_fields: &'static [&'static str],
+		fields: &'static [&'static str],
 		visitor: V,
 	) -> Result<V::Value> {
-		self.deserialize_map(visitor)
+		let missing_fields = if self.options.default_missing_fields {
+			fields
+				.iter()
+				.copied()
+				.filter(|field| {
+					!self
+						.columns
+						.iter()
+						.any(|column| self.column_matches(column, field) && !self.skip_columns.contains(&column.as_str()))
+				})
+				.collect()
+		} else {
+			vec![]
+		};
+		visitor.visit_map(RowMapAccess {
+			idx: 0,
+			de: self,
+			fields,
+			missing_fields,
+		})
 	}
 
 	fn deserialize_enum<V: Visitor<'de>>(
@@ -78,28 +319,60 @@ impl<'de> Deserializer<'de> for RowDeserializer<'de, '_, '_> {
 
 	forward_to_row_value_deserializer! {
 		deserialize_bool
+		deserialize_i8
+		deserialize_i16
+		deserialize_i32
+		deserialize_i64
+		deserialize_u8
+		deserialize_u16
+		deserialize_u32
+		deserialize_u64
+		deserialize_i128
+		deserialize_u128
 		deserialize_f32
 		deserialize_f64
+		deserialize_char
+		deserialize_str
+		deserialize_string
+		deserialize_bytes
+		deserialize_byte_buf
 		deserialize_option
 		deserialize_unit
+		deserialize_seq
 		deserialize_any
-		deserialize_byte_buf
 	}
 
 	forward_to_deserialize_any! {
-		i8 i16 i32 i64 u8 u16 u32 u64 char str string bytes
-		seq tuple_struct identifier ignored_any
+		tuple_struct identifier ignored_any
 	}
 }
 
 struct RowValue<'row, 'stmt> {
 	idx: usize,
-	row: &'row Row<'stmt>,
+	row: RowSource<'row, 'stmt>,
+	options: DeserializeOptions,
+	ignored: Option<&'row RefCell<Vec<usize>>>,
 }
 
 impl<'row> RowValue<'row, '_> {
 	fn value<T: FromSql>(&self) -> Result<T> {
-		self.row.get(self.idx).map_err(Error::from)
+		self.value_ref()?;
+		self.row.get(self.idx)
+	}
+
+	/// Returns the value borrowed from the row, checks `max_blob_len` the same way as `value()`
+	fn value_ref(&self) -> Result<ValueRef<'row>> {
+		let val = self.row.get_ref(self.idx)?;
+		if let (Some(max_blob_len), ValueRef::Blob(blob)) = (self.options.max_blob_len, val) {
+			if blob.len() > max_blob_len {
+				return Err(de::Error::custom(format_args!(
+					"blob exceeds max length: {} > {}",
+					blob.len(),
+					max_blob_len
+				)));
+			}
+		}
+		Ok(val)
 	}
 
 	fn deserialize_any_helper<V: Visitor<'row>>(self, visitor: V, value: Value) -> Result<V::Value> {
@@ -108,7 +381,20 @@ impl<'row> RowValue<'row, '_> {
 			Value::Integer(val) => visitor.visit_i64(val),
 			Value::Real(val) => visitor.visit_f64(val),
 			Value::Text(val) => visitor.visit_string(val),
-			Value::Blob(val) => visitor.visit_seq(val.into_deserializer()),
+			Value::Blob(val) => match self.options.blob_representation {
+				BlobRepresentation::Seq => visitor.visit_seq(val.into_deserializer()),
+				BlobRepresentation::Bytes => visitor.visit_byte_buf(val),
+				#[cfg(feature = "serde_json")]
+				BlobRepresentation::Base64 => visitor.visit_string(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, val)),
+			},
+		}
+	}
+
+	/// Same as `deserialize_any_helper()`, but for the target types that can't represent `NULL`
+	fn deserialize_not_null_helper<V: Visitor<'row>>(self, visitor: V, value: Value) -> Result<V::Value> {
+		match value {
+			Value::Null => Err(Error::unexpected_null()),
+			val => self.deserialize_any_helper(visitor, val),
 		}
 	}
 }
@@ -118,6 +404,14 @@ impl<'de> Deserializer<'de> for RowValue<'de, '_> {
 
 	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
 		let val = self.value()?;
+		#[cfg(feature = "serde_json")]
+		if let Value::Text(text) = &val {
+			if self.options.parse_json_text && text.starts_with(['{', '[']) {
+				if let Ok(json) = serde_json::from_str::<serde_json::Value>(text) {
+					return json.deserialize_any(visitor).map_err(de::Error::custom);
+				}
+			}
+		}
 		self.deserialize_any_helper(visitor, val)
 	}
 
@@ -125,13 +419,17 @@ impl<'de> Deserializer<'de> for RowValue<'de, '_> {
 		match self.value()? {
 			Value::Integer(val) => visitor.visit_bool(val != 0),
 			Value::Real(val) => visitor.visit_bool(val != 0.),
-			val => self.deserialize_any_helper(visitor, val),
+			val => self.deserialize_not_null_helper(visitor, val),
 		}
 	}
 
 	fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
 		match self.value()? {
 			Value::Null => visitor.visit_f32(f32::NAN),
+			Value::Integer(_) if self.options.strict_numeric => Err(Error::type_mismatch("REAL", Type::Integer)),
+			Value::Text(val) if self.options.float_from_text || self.options.numeric_from_text => {
+				visitor.visit_f32(parse_text(&val)?)
+			}
 			val => self.deserialize_any_helper(visitor, val),
 		}
 	}
@@ -139,12 +437,30 @@ impl<'de> Deserializer<'de> for RowValue<'de, '_> {
 	fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
 		match self.value()? {
 			Value::Null => visitor.visit_f64(f64::NAN),
+			Value::Integer(_) if self.options.strict_numeric => Err(Error::type_mismatch("REAL", Type::Integer)),
+			Value::Text(val) if self.options.float_from_text || self.options.numeric_from_text => {
+				visitor.visit_f64(parse_text(&val)?)
+			}
 			val => self.deserialize_any_helper(visitor, val),
 		}
 	}
 
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			Value::Text(val) if self.options.first_char => match val.chars().next() {
+				Some(val) => visitor.visit_char(val),
+				None => self.deserialize_not_null_helper(visitor, Value::Text(val)),
+			},
+			val => self.deserialize_not_null_helper(visitor, val),
+		}
+	}
+
 	fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-		visitor.visit_byte_buf(self.value()?)
+		match self.value()? {
+			Value::Null => Err(Error::unexpected_null()),
+			Value::Blob(val) => visitor.visit_byte_buf(val),
+			_ => visitor.visit_byte_buf(self.value()?),
+		}
 	}
 
 	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
@@ -164,52 +480,220 @@ impl<'de> Deserializer<'de> for RowValue<'de, '_> {
 	fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
 		match self.value()? {
 			Value::Text(ref val) if val == name => visitor.visit_unit(),
-			val => self.deserialize_any_helper(visitor, val),
+			Value::Text(ref val) if self.options.case_insensitive_unit_struct && val.eq_ignore_ascii_case(name) => {
+				visitor.visit_unit()
+			}
+			val => self.deserialize_not_null_helper(visitor, val),
 		}
 	}
 
 	fn deserialize_enum<V: Visitor<'de>>(
 		self,
-		_name: &'static str,
-		_variants: &'static [&'static str],
+		name: &'static str,
+		variants: &'static [&'static str],
 		visitor: V,
 	) -> Result<V::Value> {
-		visitor.visit_enum(RowEnumAccess(self.value()?))
+		match self.value()? {
+			Value::Null => Err(Error::unexpected_null()),
+			#[cfg(feature = "serde_json")]
+			Value::Text(val) if val.starts_with('{') => parse_json(&val)?
+				.deserialize_enum(name, variants, visitor)
+				.map_err(de::Error::custom),
+			Value::Text(val) => visitor.visit_enum(RowEnumAccess(val, self)),
+			Value::Integer(val) => match usize::try_from(val).ok().and_then(|idx| variants.get(idx)) {
+				Some(variant) => visitor.visit_enum(RowEnumAccess(variant.to_string(), self)),
+				None => Err(de::Error::custom(format_args!(
+					"INTEGER value {} is out of range for the variant index of enum {} with {} variants",
+					val,
+					name,
+					variants.len()
+				))),
+			},
+			_ => visitor.visit_enum(RowEnumAccess(self.value()?, self)),
+		}
+	}
+
+	fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			Value::Text(val) => visitor.visit_i128(parse_text(&val)?),
+			_ => self.deserialize_i64(visitor),
+		}
+	}
+
+	fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			Value::Text(val) if self.options.numeric_from_text => visitor.visit_u64(parse_text(&val)?),
+			Value::Real(_) if self.options.strict_numeric => Err(Error::type_mismatch("INTEGER", Type::Real)),
+			Value::Real(val) if self.options.integer_from_real => visitor.visit_i64(real_to_integer(val)?),
+			// values above `i64::MAX` stored by `SerializeOptions::u64_as_text()` are read regardless of the options
+			Value::Text(val) => match val.parse::<u64>() {
+				Ok(parsed) if parsed > i64::MAX as u64 => visitor.visit_u64(parsed),
+				_ => self.deserialize_not_null_helper(visitor, Value::Text(val)),
+			},
+			val => self.deserialize_not_null_helper(visitor, val),
+		}
+	}
+
+	fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			Value::Text(val) => visitor.visit_u128(parse_text(&val)?),
+			_ => self.deserialize_i64(visitor),
+		}
+	}
+
+	deserialize_integer! {
+		deserialize_i8
+		deserialize_i16
+		deserialize_i32
+		deserialize_i64
+		deserialize_u8
+		deserialize_u16
+		deserialize_u32
+	}
+
+	/// `TEXT` is passed borrowed from the row, so `&str` fields work without copying
+	fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value_ref()? {
+			ValueRef::Text(val) => visitor.visit_borrowed_str(std::str::from_utf8(val).map_err(<Error as de::Error>::custom)?),
+			_ => {
+				let val = self.value()?;
+				self.deserialize_not_null_helper(visitor, val)
+			}
+		}
+	}
+
+	/// `BLOB` and `TEXT` are passed borrowed from the row, so `&[u8]` fields work without copying
+	fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value_ref()? {
+			ValueRef::Blob(val) => visitor.visit_borrowed_bytes(val),
+			ValueRef::Text(val) => visitor.visit_borrowed_bytes(val),
+			_ => {
+				let val = self.value()?;
+				self.deserialize_not_null_helper(visitor, val)
+			}
+		}
+	}
+
+	deserialize_not_null! {
+		deserialize_string
+	}
+
+	fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			#[cfg(feature = "serde_json")]
+			Value::Text(val) => parse_json(&val)?.deserialize_seq(visitor).map_err(de::Error::custom),
+			Value::Blob(val) => visitor.visit_seq(val.into_deserializer()),
+			val => self.deserialize_any_helper(visitor, val),
+		}
+	}
+
+	/// `BLOB` fills a fixed-size array like `[u8; 32]` only if it has exactly `len` bytes
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+		match self.value()? {
+			Value::Blob(val) if val.len() == len => visitor.visit_seq(val.into_deserializer()),
+			Value::Blob(val) => Err(de::Error::custom(format_args!(
+				"BLOB of {} bytes doesn't match the expected length of {} bytes",
+				val.len(),
+				len
+			))),
+			#[cfg(feature = "serde_json")]
+			Value::Text(val) => parse_json(&val)?.deserialize_tuple(len, visitor).map_err(de::Error::custom),
+			val => self.deserialize_any_helper(visitor, val),
+		}
+	}
+
+	#[cfg(not(feature = "serde_json"))]
+	forward_to_deserialize_any! {
+		map struct
+	}
+
+	#[cfg(feature = "serde_json")]
+	deserialize_json_text! {
+		deserialize_map()
+		deserialize_struct(name: &'static str, fields: &'static [&'static str])
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value> {
+		// the placeholder column of the `RawRow` field is not a part of the captured row
+		if name == RAW_ROW_NEWTYPE {
+			return visitor.visit_byte_buf(self.row.encode_row(Some(self.idx))?.0);
+		}
+		if name == RAW_VALUE_NEWTYPE {
+			return visitor.visit_byte_buf(encode_raw_value(&self.value()?));
+		}
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		match self.ignored {
+			Some(ignored) => {
+				ignored.borrow_mut().push(self.idx);
+				visitor.visit_unit()
+			}
+			None => self.deserialize_any(visitor),
+		}
 	}
 
 	forward_to_deserialize_any! {
-		i8 i16 i32 i64 u8 u16 u32 u64 char str string bytes
-		newtype_struct seq tuple
-		tuple_struct map struct identifier ignored_any
+		tuple_struct identifier
 	}
 }
 
 struct RowMapAccess<'row, 'stmt, 'cols> {
 	idx: usize,
 	de: RowDeserializer<'row, 'stmt, 'cols>,
+	/// Struct fields, used to find the field name for the column with `case_insensitive_columns` option
+	fields: &'static [&'static str],
+	/// Struct fields that are not present in the columns, they are served after the columns
+	missing_fields: Vec<&'static str>,
 }
 
 impl<'de> MapAccess<'de> for RowMapAccess<'de, '_, '_> {
 	type Error = Error;
 
 	fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+		while self
+			.de
+			.columns
+			.get(self.idx)
+			.is_some_and(|column| self.de.skip_columns.contains(&column.as_str()))
+		{
+			self.idx += 1;
+		}
 		if self.idx >= self.de.columns.len() {
-			Ok(None)
+			match self.missing_fields.get(self.idx - self.de.columns.len()) {
+				Some(field) => seed.deserialize(field.into_deserializer()).map(Some),
+				None => Ok(None),
+			}
+		} else if self.de.index_keys {
+			seed.deserialize((self.idx as u64).into_deserializer()).map(Some)
 		} else {
 			let column = self.de.columns[self.idx].as_str();
+			let key = if self.de.options.case_insensitive_columns && !self.fields.contains(&column) {
+				self
+					.fields
+					.iter()
+					.copied()
+					.find(|field| field.eq_ignore_ascii_case(column))
+					.unwrap_or(column)
+			} else {
+				column
+			};
 			seed
-				.deserialize(column.into_deserializer())
+				.deserialize(key.into_deserializer())
 				.map(Some)
 				.map_err(|e| add_field_to_error(e, column))
 		}
 	}
 
 	fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+		if self.idx >= self.de.columns.len() {
+			let field = self.missing_fields[self.idx - self.de.columns.len()];
+			self.idx += 1;
+			return seed.deserialize(MissingValue).map_err(|e| add_field_to_error(e, field));
+		}
 		let out = seed
-			.deserialize(RowValue {
-				idx: self.idx,
-				row: self.de.row,
-			})
+			.deserialize(self.de.row_value_at(self.idx))
 			.map_err(|e| add_field_to_error(e, &self.de.columns[self.idx]));
 		self.idx += 1;
 		out
@@ -218,6 +702,8 @@ impl<'de> MapAccess<'de> for RowMapAccess<'de, '_, '_> {
 
 struct RowSeqAccess<'row, 'stmt, 'cols> {
 	idx: usize,
+	/// Length of the target tuple
+	len: usize,
 	de: RowDeserializer<'row, 'stmt, 'cols>,
 }
 
@@ -225,40 +711,52 @@ impl<'de> SeqAccess<'de> for RowSeqAccess<'de, '_, '_> {
 	type Error = Error;
 
 	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+		let found = self.de.row.len().saturating_sub(self.de.offset);
+		if self.idx >= found {
+			return Err(Error::ColumnCountMismatch {
+				expected: self.len,
+				found,
+				row: None,
+			});
+		}
 		let out = seed
-			.deserialize(RowValue {
-				idx: self.idx,
-				row: self.de.row,
-			})
+			.deserialize(self.de.row_value_at(self.idx))
 			.map(Some)
-			.map_err(|e| add_field_to_error(e, &self.de.columns[self.idx]));
+			.map_err(|e| match self.de.columns.get(self.idx) {
+				Some(column) => add_field_to_error(e, column),
+				None => e,
+			});
 		self.idx += 1;
 		out
 	}
 }
 
-struct RowEnumAccess(String);
+/// Access to the `enum` stored as the variant name, the value is kept to be used as the data of a newtype variant
+struct RowEnumAccess<'row, 'stmt>(String, RowValue<'row, 'stmt>);
 
-impl<'de> EnumAccess<'de> for RowEnumAccess {
+impl<'de, 'stmt> EnumAccess<'de> for RowEnumAccess<'de, 'stmt> {
 	type Error = Error;
-	type Variant = RowVariantAccess;
+	type Variant = RowVariantAccess<'de, 'stmt>;
 
 	fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
-		seed.deserialize(self.0.into_deserializer()).map(|v| (v, RowVariantAccess))
+		seed
+			.deserialize(self.0.into_deserializer())
+			.map(|v| (v, RowVariantAccess(self.1)))
 	}
 }
 
-struct RowVariantAccess;
+struct RowVariantAccess<'row, 'stmt>(RowValue<'row, 'stmt>);
 
-impl<'de> VariantAccess<'de> for RowVariantAccess {
+impl<'de> VariantAccess<'de> for RowVariantAccess<'de, '_> {
 	type Error = Error;
 
 	fn unit_variant(self) -> Result<()> {
 		Ok(())
 	}
 
-	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _seed: T) -> Result<T::Value> {
-		Err(Error::de_unsupported("newtype_variant"))
+	/// The data is deserialized from the same column that holds the variant name
+	fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+		seed.deserialize(self.0)
 	}
 	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
 		Err(Error::de_unsupported("tuple_variant"))
@@ -268,8 +766,44 @@ impl<'de> VariantAccess<'de> for RowVariantAccess {
 	}
 }
 
+fn parse_text<T: FromStr>(val: &str) -> Result<T>
+where
+	T::Err: fmt::Display,
+{
+	val.parse().map_err(|e| {
+		de::Error::custom(format_args!(
+			"can't parse TEXT value \"{}\" as {}: {}",
+			val,
+			any::type_name::<T>(),
+			e
+		))
+	})
+}
+
+/// Largest integer `n` such that `n` and all smaller integers are exactly representable by `f64` and can't be a rounded
+/// result of a larger integer
+const MAX_SAFE_INTEGER: f64 = ((1_i64 << f64::MANTISSA_DIGITS) - 1) as f64;
+
+fn real_to_integer(val: f64) -> Result<i64> {
+	if val.fract() == 0. && val.abs() <= MAX_SAFE_INTEGER {
+		Ok(val as i64)
+	} else {
+		Err(de::Error::custom(format_args!(
+			"REAL value {} can't be exactly represented as an integer",
+			val
+		)))
+	}
+}
+
+#[cfg(feature = "serde_json")]
+fn parse_json(val: &str) -> Result<serde_json::Value> {
+	serde_json::from_str(val).map_err(de::Error::custom)
+}
+
 fn add_field_to_error(mut error: Error, error_column: &str) -> Error {
-	if let Error::Deserialization { column, .. } = &mut error {
+	if let Error::Deserialization { column, .. } | Error::TypeMismatch { column, .. } | Error::UnexpectedNull { column, .. } =
+		&mut error
+	{
 		*column = Some(error_column.to_string());
 	}
 	error
@@ -0,0 +1,154 @@
+/// Representation of `BLOB` values for the self-describing deserialization
+///
+/// Types like `serde_json::Value` or generic maps don't tell the deserializer what they expect and accept whatever is
+/// stored in the column (serde `deserialize_any()`), this defines what they get for a `BLOB`. Types that ask for bytes or a
+/// sequence explicitly (e.g. `Vec<u8>` or `serde_bytes::ByteBuf`) are not affected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlobRepresentation {
+	/// Sequence of `u8`, e.g. an array of numbers in `serde_json::Value`
+	#[default]
+	Seq,
+	/// Byte buffer, for the types that support it
+	///
+	/// Note that `serde_json::Value` doesn't accept bytes and fails with an error, use `Base64` for it instead.
+	Bytes,
+	/// String with the standard base64 encoding (with padding), the same as `row_to_json()` produces
+	///
+	/// This makes `serde_json::Value` receive a string instead of an array of numbers.
+	#[cfg(feature = "serde_json")]
+	Base64,
+}
+
+/// Options that change the way row values are deserialized
+///
+/// The default options produce the same result as the crate's `from_row()` and `from_rows()` functions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeOptions {
+	pub(crate) blob_representation: BlobRepresentation,
+	pub(crate) float_from_text: bool,
+	pub(crate) numeric_from_text: bool,
+	pub(crate) default_missing_fields: bool,
+	pub(crate) strict_numeric: bool,
+	pub(crate) case_insensitive_unit_struct: bool,
+	pub(crate) case_insensitive_columns: bool,
+	pub(crate) integer_from_real: bool,
+	pub(crate) first_char: bool,
+	pub(crate) max_blob_len: Option<usize>,
+	#[cfg(feature = "serde_json")]
+	pub(crate) parse_json_text: bool,
+}
+
+impl DeserializeOptions {
+	/// Set the way `BLOB` values are presented to the types that don't specify what they expect
+	///
+	/// Check `BlobRepresentation` for details.
+	pub fn blob_representation(mut self, blob_representation: BlobRepresentation) -> Self {
+		self.blob_representation = blob_representation;
+		self
+	}
+
+	/// Parse `TEXT` values into `f32` and `f64`
+	///
+	/// This is the counterpart of `SerializeOptions::float_as_text()`. `REAL` and `NULL` values are still handled as usual.
+	pub fn float_from_text(mut self, float_from_text: bool) -> Self {
+		self.float_from_text = float_from_text;
+		self
+	}
+
+	/// Parse `TEXT` values into integer and float targets with `str::parse()`
+	///
+	/// Useful for the tables that store numbers as `TEXT`. Unparsable text fails with `Error::Deserialization` naming the
+	/// column. Off by default because silent coercion can hide a wrong column or schema. Implies `float_from_text()`.
+	pub fn numeric_from_text(mut self, numeric_from_text: bool) -> Self {
+		self.numeric_from_text = numeric_from_text;
+		self
+	}
+
+	/// Use default values for `struct` fields that don't have a corresponding column
+	///
+	/// Without this option a field that is missing from the columns must be an `Option` or have `#[serde(default)]`
+	/// attribute, otherwise deserialization fails with "missing field" error. With this option such fields receive the
+	/// same values `Default::default()` returns for the standard types: zeroes, `false`, empty strings and collections and
+	/// `None`. Types with custom `Default` implementation still need `#[serde(default)]` to get that value. Enums can't be
+	/// defaulted this way.
+	///
+	/// Note that the names of the fields (after `#[serde(rename)]`) are checked against the columns, so if the column for a
+	/// field is selected under one of its `#[serde(alias)]` names the deserialization fails with "duplicate field" error.
+	pub fn default_missing_fields(mut self, default_missing_fields: bool) -> Self {
+		self.default_missing_fields = default_missing_fields;
+		self
+	}
+
+	/// Reject values of numeric storage class that doesn't match the target type
+	///
+	/// By default an `INTEGER` value is silently converted when deserializing into a float. With this option reading a `REAL`
+	/// into an integer or an `INTEGER` into a float fails with `Error::TypeMismatch`.
+	pub fn strict_numeric(mut self, strict_numeric: bool) -> Self {
+		self.strict_numeric = strict_numeric;
+		self
+	}
+
+	/// Accept `REAL` values for integer targets when they are exactly representable as integers
+	///
+	/// By default reading a `REAL` into an integer fails. With this option values without the fractional part are converted,
+	/// but only up to 2^53 - 1 in magnitude: larger `REAL`s could be the result of rounding of a different integer during
+	/// storing (e.g. an `i64` written into a `REAL` column), so they produce an error instead of silently losing precision.
+	/// `strict_numeric()` takes precedence over this option.
+	pub fn integer_from_real(mut self, integer_from_real: bool) -> Self {
+		self.integer_from_real = integer_from_real;
+		self
+	}
+
+	/// Compare the `TEXT` value with the name of the `unit_struct` ignoring ASCII case
+	///
+	/// By default the stored text must be exactly the same as the name of the `struct`, with this option e.g. `MARKER` and
+	/// `marker` are also accepted for `struct Marker;`.
+	pub fn case_insensitive_unit_struct(mut self, case_insensitive_unit_struct: bool) -> Self {
+		self.case_insensitive_unit_struct = case_insensitive_unit_struct;
+		self
+	}
+
+	/// Match column names to `struct` fields ignoring ASCII case
+	///
+	/// With this option e.g. `ID` and `Id` columns both fill the `id` field. The field name is passed to serde as written in
+	/// the `struct`, so `#[serde(rename)]` still works, and the error messages keep referring to the original column name.
+	/// Maps still receive the column names unchanged. This includes the `struct`s with `#[serde(flatten)]` fields, serde
+	/// deserializes them as maps without the list of fields, so their columns are still matched case-sensitively.
+	pub fn case_insensitive_columns(mut self, case_insensitive_columns: bool) -> Self {
+		self.case_insensitive_columns = case_insensitive_columns;
+		self
+	}
+
+	/// Take the first character of a longer `TEXT` value for `char` targets
+	///
+	/// By default the `TEXT` must contain exactly one character. Empty `TEXT` still produces an error with this option.
+	pub fn first_char(mut self, first_char: bool) -> Self {
+		self.first_char = first_char;
+		self
+	}
+
+	/// Parse `TEXT` holding a JSON object or array for the self-describing targets like `serde_json::Value`
+	///
+	/// Such targets don't tell what they expect, so by default they receive `TEXT` as a string. With this option `TEXT`
+	/// that starts with `{` or `[` and is valid JSON is passed to them as a map or sequence instead, which makes the
+	/// objects and arrays stored by `SerializeOptions::compound_as_json()` round-trip. Note that this also applies to the
+	/// strings that merely look like JSON, e.g. a `serde_json::Value::String("[1]")` comes back as an array, and to the
+	/// string fields that are deserialized through `#[serde(flatten)]` or untagged `enum`s, so only enable it for the rows
+	/// where that's expected. The types that ask for a map, `struct` or sequence parse JSON `TEXT` regardless of this
+	/// option.
+	#[cfg(feature = "serde_json")]
+	pub fn parse_json_text(mut self, parse_json_text: bool) -> Self {
+		self.parse_json_text = parse_json_text;
+		self
+	}
+
+	/// Fail with `Error::Deserialization` on `BLOB` values longer than `max_blob_len` bytes
+	///
+	/// The length is checked before the value is copied out of the row, so this guards the memory when reading untrusted
+	/// databases. The limit applies to every target type, including `Vec<u8>`, `serde_bytes::ByteBuf` and the
+	/// self-describing ones. `None`, the default, disables the check.
+	pub fn max_blob_len(mut self, max_blob_len: Option<usize>) -> Self {
+		self.max_blob_len = max_blob_len;
+		self
+	}
+}
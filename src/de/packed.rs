@@ -0,0 +1,135 @@
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+
+use crate::{Error, Result};
+
+macro_rules! deserialize_le {
+	($($fun:ident => $visit:ident($type:ty);)*) => {
+		$(
+			fn $fun<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+				let mut bytes = [0; size_of::<$type>()];
+				bytes.copy_from_slice(self.take(size_of::<$type>())?);
+				visitor.$visit(<$type>::from_le_bytes(bytes))
+			}
+		)*
+	}
+}
+
+/// Deserializer for a fixed-size record packed into bytes
+///
+/// Integers and floats are read as little-endian values of their natural size, `bool` is a single byte where anything but
+/// 0 is `true`, `char` is a 4-byte code point. `struct`s, tuples and arrays are read field by field without any padding.
+/// Variable-length types like strings, sequences, maps and `Option`s are not supported.
+pub struct PackedDeserializer<'a> {
+	input: &'a [u8],
+}
+
+impl<'a> PackedDeserializer<'a> {
+	pub fn new(input: &'a [u8]) -> Self {
+		Self { input }
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		if len > self.input.len() {
+			return Err(de::Error::custom(format_args!(
+				"packed record is too short, {} more byte(s) needed",
+				len - self.input.len()
+			)));
+		}
+		let (out, rest) = self.input.split_at(len);
+		self.input = rest;
+		Ok(out)
+	}
+}
+
+impl<'de> Deserializer<'de> for &mut PackedDeserializer<'_> {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+		Err(Error::de_unsupported("packed record field of unknown type"))
+	}
+
+	fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_bool(self.take(1)?[0] != 0)
+	}
+
+	fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		let mut bytes = [0; 4];
+		bytes.copy_from_slice(self.take(4)?);
+		let code = u32::from_le_bytes(bytes);
+		match char::from_u32(code) {
+			Some(val) => visitor.visit_char(val),
+			None => Err(de::Error::custom(format_args!("invalid char code point: {:#x}", code))),
+		}
+	}
+
+	deserialize_le! {
+		deserialize_i8 => visit_i8(i8);
+		deserialize_i16 => visit_i16(i16);
+		deserialize_i32 => visit_i32(i32);
+		deserialize_i64 => visit_i64(i64);
+		deserialize_i128 => visit_i128(i128);
+		deserialize_u8 => visit_u8(u8);
+		deserialize_u16 => visit_u16(u16);
+		deserialize_u32 => visit_u32(u32);
+		deserialize_u64 => visit_u64(u64);
+		deserialize_u128 => visit_u128(u128);
+		deserialize_f32 => visit_f32(f32);
+		deserialize_f64 => visit_f64(f64);
+	}
+
+	fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+		visitor.visit_seq(PackedSeqAccess { de: self, left: len })
+	}
+
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value> {
+		self.deserialize_tuple(len, visitor)
+	}
+
+	fn deserialize_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value> {
+		self.deserialize_tuple(fields.len(), visitor)
+	}
+
+	forward_to_deserialize_any! {
+		str string bytes byte_buf option seq
+		map enum identifier ignored_any
+	}
+}
+
+struct PackedSeqAccess<'a, 'b> {
+	de: &'a mut PackedDeserializer<'b>,
+	left: usize,
+}
+
+impl<'de> SeqAccess<'de> for PackedSeqAccess<'_, '_> {
+	type Error = Error;
+
+	fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+		if self.left == 0 {
+			return Ok(None);
+		}
+		self.left -= 1;
+		seed.deserialize(&mut *self.de).map(Some)
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.left)
+	}
+}
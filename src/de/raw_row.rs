@@ -0,0 +1,101 @@
+use std::fmt;
+
+use rusqlite::types::Value;
+use serde::de::{self, Visitor};
+use serde::Deserializer;
+
+use crate::ser::{decode_raw_value, encode_raw_value};
+use crate::Result;
+
+/// Name of the newtype struct that receives all values of the row encoded into bytes, check `RawRow`
+pub const RAW_ROW_NEWTYPE: &str = "__serde_rusqlite_raw_row";
+
+/// Field type that captures the copy of the whole source row in a compact binary form
+///
+/// Use it as a field of the `struct` alongside the normal fields:
+///
+/// ```
+/// use rusqlite::types::Value;
+/// use serde_derive::Deserialize;
+/// use serde_rusqlite::de::RawRow;
+///
+/// #[derive(Deserialize)]
+/// struct Audit {
+///    id: i64,
+///    raw: RawRow,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let audit = connection
+///    .query_row("SELECT 1 AS id, 'x' AS name, NULL AS raw", [], |row| Ok(serde_rusqlite::from_row::<Audit>(row).unwrap()))
+///    .unwrap();
+/// assert_eq!(audit.id, 1);
+/// assert_eq!(audit.raw.values().unwrap(), [Value::Integer(1), Value::Text("x".into())]);
+/// ```
+///
+/// The field is filled when the deserializer reaches the column with the same name as the field, so select a placeholder
+/// column (like `NULL AS raw` above) for it. The placeholder column itself is left out of the captured values, while
+/// `from_row::<RawRow>()` captures all columns of the row. The encoding is a sequence of the values each prefixed with its
+/// length as little-endian `u32`, the value itself is encoded the same way as for `ser::RAW_VALUE_NEWTYPE`. Use `values()`
+/// to decode it back.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RawRow(pub Vec<u8>);
+
+impl RawRow {
+	/// Encodes `values` of the row
+	pub fn encode<'v>(values: impl IntoIterator<Item = &'v Value>) -> Self {
+		let mut out = vec![];
+		for value in values {
+			let encoded = encode_raw_value(value);
+			out.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+			out.extend_from_slice(&encoded);
+		}
+		Self(out)
+	}
+
+	/// Decodes the captured values of the row in the order of the columns
+	pub fn values(&self) -> Result<Vec<Value>> {
+		let invalid = || de::Error::custom(format_args!("invalid encoding of the {} value", RAW_ROW_NEWTYPE));
+		let mut out = vec![];
+		let mut rest = self.0.as_slice();
+		while !rest.is_empty() {
+			let (len, tail) = rest.split_first_chunk::<4>().ok_or_else(invalid)?;
+			let len = u32::from_le_bytes(*len) as usize;
+			if tail.len() < len {
+				return Err(invalid());
+			}
+			let (value, tail) = tail.split_at(len);
+			out.push(decode_raw_value(value).map_err(|_| invalid())?);
+			rest = tail;
+		}
+		Ok(out)
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for RawRow {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(RAW_ROW_NEWTYPE, RawRowVisitor)
+	}
+}
+
+struct RawRowVisitor;
+
+impl<'de> Visitor<'de> for RawRowVisitor {
+	type Value = RawRow;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("encoded row")
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+		Ok(RawRow(v.to_vec()))
+	}
+
+	fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Self::Value, E> {
+		Ok(RawRow(v))
+	}
+
+	fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_byte_buf(self)
+	}
+}
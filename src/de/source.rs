@@ -0,0 +1,98 @@
+use rusqlite::types::{FromSql, FromSqlError, Value, ValueRef};
+use rusqlite::Row;
+
+use super::RawRow;
+use crate::{Error, Result};
+
+/// Storage of the values that are being deserialized
+#[derive(Clone, Copy)]
+pub enum RowSource<'row, 'stmt> {
+	/// Row from the query result
+	Row(&'row Row<'stmt>),
+	/// Row assembled in memory as pairs of column name and value
+	Values(&'row [(String, Value)]),
+	/// Values of a single column collected from all rows, indexed by the row number
+	Column {
+		idx: usize,
+		name: &'row str,
+		values: &'row [Value],
+	},
+}
+
+impl<'row> RowSource<'row, '_> {
+	pub fn get_ref(self, idx: usize) -> Result<ValueRef<'row>> {
+		match self {
+			RowSource::Row(row) => Ok(row.get_ref(idx)?),
+			RowSource::Values(values) => match values.get(idx) {
+				Some((_, value)) => Ok(ValueRef::from(value)),
+				None => Err(rusqlite::Error::InvalidColumnIndex(idx).into()),
+			},
+			RowSource::Column { values, .. } => match values.get(idx) {
+				Some(value) => Ok(ValueRef::from(value)),
+				None => Err(rusqlite::Error::InvalidColumnIndex(idx).into()),
+			},
+		}
+	}
+
+	/// Number of the values available for reading
+	pub fn len(self) -> usize {
+		match self {
+			RowSource::Row(row) => row.as_ref().column_count(),
+			RowSource::Values(values) => values.len(),
+			RowSource::Column { values, .. } => values.len(),
+		}
+	}
+
+	/// Encodes all values of the row except the one at `skip`, not available for the values of a single column
+	pub fn encode_row(self, skip: Option<usize>) -> Result<RawRow> {
+		match self {
+			RowSource::Row(row) => {
+				let values = (0..row.as_ref().column_count())
+					.filter(|&idx| Some(idx) != skip)
+					.map(|idx| row.get::<_, Value>(idx))
+					.collect::<rusqlite::Result<Vec<_>>>()?;
+				Ok(RawRow::encode(&values))
+			}
+			RowSource::Values(values) => Ok(RawRow::encode(
+				values
+					.iter()
+					.enumerate()
+					.filter(|&(idx, _)| Some(idx) != skip)
+					.map(|(_, (_, value))| value),
+			)),
+			RowSource::Column { .. } => Err(Error::de_unsupported(super::RAW_ROW_NEWTYPE)),
+		}
+	}
+
+	/// Same as `rusqlite::Row::get()`, the errors for in-memory values are also reported the same way
+	pub fn get<T: FromSql>(self, idx: usize) -> Result<T> {
+		match self {
+			RowSource::Row(row) => Ok(row.get(idx)?),
+			RowSource::Values(values) => {
+				let (column, value) = values.get(idx).ok_or(rusqlite::Error::InvalidColumnIndex(idx))?;
+				value_result(idx, column, value)
+			}
+			RowSource::Column {
+				idx: column_idx,
+				name,
+				values,
+			} => {
+				let value = values.get(idx).ok_or(rusqlite::Error::InvalidColumnIndex(idx))?;
+				value_result(column_idx, name, value)
+			}
+		}
+	}
+}
+
+/// Converts the in-memory `value` of the column `idx` named `column` reporting the errors the same way as rusqlite
+fn value_result<T: FromSql>(idx: usize, column: &str, value: &Value) -> Result<T> {
+	T::column_result(ValueRef::from(value)).map_err(|e| {
+		match e {
+			FromSqlError::InvalidType => rusqlite::Error::InvalidColumnType(idx, column.to_owned(), value.data_type()),
+			FromSqlError::OutOfRange(val) => rusqlite::Error::IntegralValueOutOfRange(idx, val),
+			FromSqlError::Other(err) => rusqlite::Error::FromSqlConversionFailure(idx, value.data_type(), err),
+			e => rusqlite::Error::FromSqlConversionFailure(idx, value.data_type(), Box::new(e)),
+		}
+		.into()
+	})
+}
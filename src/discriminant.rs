@@ -0,0 +1,59 @@
+//! Helpers to store C-like `enum`s as `INTEGER` using their explicit discriminants
+//!
+//! serde only passes the position of the variant to the serializer, so `SerializeOptions::enum_as_index()` stores
+//! `enum Code { Ok = 200, NotFound = 404 }` as 0 and 1. Implement `Discriminant` to provide the mapping between the
+//! variants and the codes and use this module with `#[serde(with = "...")]` to store 200 and 404 instead. Reading back a
+//! code that doesn't map to any variant fails.
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//! use serde_rusqlite::discriminant::Discriminant;
+//!
+//! #[derive(Clone, Copy)]
+//! enum Code {
+//!    Ok = 200,
+//!    NotFound = 404,
+//! }
+//!
+//! impl Discriminant for Code {
+//!    fn discriminant(&self) -> i64 {
+//!       *self as i64
+//!    }
+//!
+//!    fn from_discriminant(discriminant: i64) -> Option<Self> {
+//!       match discriminant {
+//!          200 => Some(Self::Ok),
+//!          404 => Some(Self::NotFound),
+//!          _ => None,
+//!       }
+//!    }
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Response {
+//!    #[serde(with = "serde_rusqlite::discriminant")]
+//!    code: Code,
+//! }
+//! ```
+//!
+//! Alternatively the same can be achieved for all uses of the type with `#[serde(into = "i64", try_from = "i64")]` on the
+//! `enum` itself.
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Mapping between the variants of an `enum` and the `INTEGER`s stored in the database
+pub trait Discriminant: Sized {
+	fn discriminant(&self) -> i64;
+
+	fn from_discriminant(discriminant: i64) -> Option<Self>;
+}
+
+pub fn serialize<T: Discriminant, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_i64(value.discriminant())
+}
+
+pub fn deserialize<'de, T: Discriminant, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+	let discriminant = i64::deserialize(deserializer)?;
+	T::from_discriminant(discriminant)
+		.ok_or_else(|| de::Error::invalid_value(de::Unexpected::Signed(discriminant), &"a known enum discriminant"))
+}
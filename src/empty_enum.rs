@@ -0,0 +1,38 @@
+//! Helpers to store `Option` of a unit-only `enum` using empty `TEXT` instead of `NULL` for `None`
+//!
+//! Useful for the schemas that use an empty string for the missing discriminant. `None` is stored as `''` and both `''` and
+//! `NULL` are read back as `None`, all other values select the variant by name as usual. Only the `enum` fields that use
+//! this module are affected, so `Option<String>` fields still read `''` as `Some("")`.
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! enum Status {
+//!    Active,
+//!    Closed,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::empty_enum")]
+//!    status: Option<Status>,
+//! }
+//! ```
+
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<E: Serialize, S: Serializer>(value: &Option<E>, serializer: S) -> Result<S::Ok, S::Error> {
+	match value {
+		Some(value) => value.serialize(serializer),
+		None => serializer.serialize_str(""),
+	}
+}
+
+pub fn deserialize<'de, E: Deserialize<'de>, D: Deserializer<'de>>(deserializer: D) -> Result<Option<E>, D::Error> {
+	match Option::<String>::deserialize(deserializer)? {
+		Some(variant) if !variant.is_empty() => E::deserialize(variant.into_deserializer()).map(Some),
+		_ => Ok(None),
+	}
+}
@@ -1,7 +1,13 @@
 use std::{error::Error as StdError, fmt, result};
 
+use rusqlite::types::Type;
 use serde::{de, ser};
 
+/// Errors of serialization and deserialization
+///
+/// `PartialEq` is implemented mostly to simplify the assertions in tests. Variants are compared field by field except for
+/// `Rusqlite`, which is compared by `to_string()` because the `rusqlite::Error`s carrying a boxed source error like
+/// `FromSqlConversionFailure` can't be compared structurally.
 #[derive(Debug)]
 pub enum Error {
 	/// This type of serialization or deserialization is not supported
@@ -11,11 +17,50 @@ pub enum Error {
 	/// General error during serialization
 	Serialization(String),
 	/// General error during deserialization
-	Deserialization { column: Option<String>, message: String },
+	///
+	/// `row` is the 0-based index of the row in the `rusqlite::Rows` when the error comes from one of the row iterators like
+	/// `from_rows()`.
+	Deserialization {
+		column: Option<String>,
+		row: Option<usize>,
+		message: String,
+	},
+	/// The storage class of the value doesn't match the target type, e.g. `REAL` for an integer in strict numeric mode
+	///
+	/// `row` is filled the same way as for `Deserialization`.
+	TypeMismatch {
+		column: Option<String>,
+		row: Option<usize>,
+		expected: &'static str,
+		found: Type,
+	},
+	/// `NULL` value was read for the target type that can't represent it, e.g. non-`Option` integer
+	///
+	/// `row` is filled the same way as for `Deserialization`.
+	UnexpectedNull { column: Option<String>, row: Option<usize> },
 	/// Error originating from rusqlite
 	Rusqlite(rusqlite::Error),
 	/// No column name information available
 	ColumnNamesNotAvailable,
+	/// The row has fewer columns than the target tuple has elements
+	///
+	/// `row` is filled the same way as for `Deserialization`.
+	ColumnCountMismatch {
+		expected: usize,
+		found: usize,
+		row: Option<usize>,
+	},
+	/// The fields of a `struct` don't match the columns of a table, see `validate_struct_against_table()`
+	SchemaMismatch {
+		table: String,
+		/// Fields that don't have a corresponding column
+		missing: Vec<String>,
+		/// Columns that don't have a corresponding field
+		extra: Vec<String>,
+	},
+	/// Error without the `row` field (e.g. `Rusqlite`) that happened while deserializing the row with the 0-based index `row`
+	/// in one of the row iterators like `from_rows()`
+	InRow { row: usize, source: Box<Error> },
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -30,6 +75,21 @@ impl Error {
 	pub fn de_unsupported(typ: &str) -> Self {
 		Error::Unsupported(format!("Deserialization is not supported into type: {}", typ))
 	}
+
+	/// Create the instance of `UnexpectedNull` `Error`, the column is filled in later
+	pub fn unexpected_null() -> Self {
+		Error::UnexpectedNull { column: None, row: None }
+	}
+
+	/// Create the instance of `TypeMismatch` `Error`, the column is filled in later
+	pub fn type_mismatch(expected: &'static str, found: Type) -> Self {
+		Error::TypeMismatch {
+			column: None,
+			row: None,
+			expected,
+			found,
+		}
+	}
 }
 
 impl fmt::Display for Error {
@@ -39,11 +99,151 @@ impl fmt::Display for Error {
 			Error::Serialization(s) => write!(f, "Serialization error: {}", s),
 			Error::Deserialization {
 				column: Some(column),
+				row: Some(row),
+				message,
+			} => write!(
+				f,
+				"Deserialization failed for column: {} in row: {} error: {}",
+				column, row, message
+			),
+			Error::Deserialization {
+				column: Some(column),
+				row: None,
 				message,
 			} => write!(f, "Deserialization failed for column: {} error: {}", column, message),
+			Error::Deserialization {
+				column: None,
+				row: Some(row),
+				message,
+			} => write!(f, "Deserialization failed in row: {} error: {}", row, message),
 			Error::Deserialization { message, .. } => write!(f, "Deserialization error: {}", message),
+			Error::TypeMismatch {
+				column: Some(column),
+				row,
+				expected,
+				found,
+			} => {
+				write!(f, "Type mismatch for column: {}", column)?;
+				write_row(f, *row)?;
+				write!(f, " expected: {} found: {}", expected, found)
+			}
+			Error::TypeMismatch {
+				column: None,
+				row,
+				expected,
+				found,
+			} => {
+				write!(f, "Type mismatch")?;
+				write_row(f, *row)?;
+				write!(f, " expected: {} found: {}", expected, found)
+			}
+			Error::UnexpectedNull { column, row } => {
+				write!(f, "Unexpected NULL value")?;
+				if let Some(column) = column {
+					write!(f, " in column: {}", column)?;
+				}
+				write_row(f, *row)
+			}
 			Error::Rusqlite(s) => write!(f, "Rusqlite error: {}", s),
 			Error::ColumnNamesNotAvailable => write!(f, "Column names are not available"),
+			Error::ColumnCountMismatch {
+				expected,
+				found,
+				row: Some(row),
+			} => write!(
+				f,
+				"Column count mismatch: expected {} column(s), but the row: {} has {}",
+				expected, row, found
+			),
+			Error::ColumnCountMismatch {
+				expected,
+				found,
+				row: None,
+			} => write!(
+				f,
+				"Column count mismatch: expected {} column(s), but the row has {}",
+				expected, found
+			),
+			Error::SchemaMismatch { table, missing, extra } => write!(
+				f,
+				"Schema mismatch for table: {} missing columns: [{}] extra columns: [{}]",
+				table,
+				missing.join(", "),
+				extra.join(", ")
+			),
+			Error::InRow { row, source } => write!(f, "{} in row: {}", source, row),
+		}
+	}
+}
+
+fn write_row(f: &mut fmt::Formatter, row: Option<usize>) -> fmt::Result {
+	match row {
+		Some(row) => write!(f, " in row: {}", row),
+		None => Ok(()),
+	}
+}
+
+impl PartialEq for Error {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Error::Unsupported(a), Error::Unsupported(b))
+			| (Error::ValueTooLarge(a), Error::ValueTooLarge(b))
+			| (Error::Serialization(a), Error::Serialization(b)) => a == b,
+			(
+				Error::Deserialization { column, row, message },
+				Error::Deserialization {
+					column: other_column,
+					row: other_row,
+					message: other_message,
+				},
+			) => column == other_column && row == other_row && message == other_message,
+			(
+				Error::TypeMismatch {
+					column,
+					row,
+					expected,
+					found,
+				},
+				Error::TypeMismatch {
+					column: other_column,
+					row: other_row,
+					expected: other_expected,
+					found: other_found,
+				},
+			) => column == other_column && row == other_row && expected == other_expected && found == other_found,
+			(
+				Error::UnexpectedNull { column, row },
+				Error::UnexpectedNull {
+					column: other_column,
+					row: other_row,
+				},
+			) => column == other_column && row == other_row,
+			(Error::Rusqlite(a), Error::Rusqlite(b)) => a.to_string() == b.to_string(),
+			(Error::ColumnNamesNotAvailable, Error::ColumnNamesNotAvailable) => true,
+			(
+				Error::ColumnCountMismatch { expected, found, row },
+				Error::ColumnCountMismatch {
+					expected: other_expected,
+					found: other_found,
+					row: other_row,
+				},
+			) => expected == other_expected && found == other_found && row == other_row,
+			(
+				Error::SchemaMismatch { table, missing, extra },
+				Error::SchemaMismatch {
+					table: other_table,
+					missing: other_missing,
+					extra: other_extra,
+				},
+			) => table == other_table && missing == other_missing && extra == other_extra,
+			(
+				Error::InRow { row, source },
+				Error::InRow {
+					row: other_row,
+					source: other_source,
+				},
+			) => row == other_row && source == other_source,
+			_ => false,
 		}
 	}
 }
@@ -52,11 +252,16 @@ impl StdError for Error {
 	fn source(&self) -> Option<&(dyn StdError + 'static)> {
 		match self {
 			Error::Rusqlite(e) => Some(e),
+			Error::InRow { source, .. } => Some(source),
 			Error::Unsupported(_)
 			| Error::ValueTooLarge(_)
 			| Error::Serialization(_)
 			| Error::Deserialization { .. }
-			| Error::ColumnNamesNotAvailable => None,
+			| Error::TypeMismatch { .. }
+			| Error::UnexpectedNull { .. }
+			| Error::ColumnNamesNotAvailable
+			| Error::ColumnCountMismatch { .. }
+			| Error::SchemaMismatch { .. } => None,
 		}
 	}
 }
@@ -65,6 +270,7 @@ impl de::Error for Error {
 	fn custom<T: fmt::Display>(msg: T) -> Self {
 		Error::Deserialization {
 			column: None,
+			row: None,
 			message: msg.to_string(),
 		}
 	}
@@ -81,3 +287,18 @@ impl From<rusqlite::Error> for Error {
 		Error::Rusqlite(e)
 	}
 }
+
+/// Allows using `?` on the results of this crate in the closures and functions returning `rusqlite::Result`
+///
+/// `Error::Rusqlite` is unwrapped back into the original `rusqlite::Error`, all other variants become
+/// `rusqlite::Error::ToSqlConversionFailure` with the error boxed as is, so the message is preserved and the original can be
+/// recovered with `downcast_ref::<serde_rusqlite::Error>()`. `FromSqlConversionFailure` isn't used for the deserialization
+/// errors because it requires the column index, which is not known here.
+impl From<Error> for rusqlite::Error {
+	fn from(e: Error) -> Self {
+		match e {
+			Error::Rusqlite(e) => e,
+			e => rusqlite::Error::ToSqlConversionFailure(Box::new(e)),
+		}
+	}
+}
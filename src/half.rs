@@ -0,0 +1,72 @@
+//! Helpers to store `half::f16` half-precision floats
+//!
+//! Two storage modes are available:
+//!
+//! * [f16_real] stores the value widened to `f64` as `REAL`. Every `f16` is exactly representable as `f64`, so the
+//!   values written by it round-trip exactly, and the column can be used in SQL arithmetic and comparisons. Values
+//!   written by other means (including `INTEGER`s) are rounded to the nearest `f16` when read, which loses precision and
+//!   turns out of range values into infinities.
+//! * [f16_blob] stores the raw IEEE 754 bits as 2 byte little-endian `BLOB`. It's exact for all the values including
+//!   `NaN` payloads (`NaN` in [f16_real] mode becomes `NULL`) and more compact, but opaque to SQL.
+//!
+//! Use them with `#[serde(with = "...")]` field attribute:
+//!
+//! ```
+//! use half::f16;
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::half::f16_real")]
+//!    weight: f16,
+//!    #[serde(with = "serde_rusqlite::half::f16_blob")]
+//!    bias: f16,
+//! }
+//! ```
+
+/// Stores `half::f16` as `REAL`, reads it from `REAL` or `INTEGER` rounding to the nearest `f16`
+pub mod f16_real {
+	use ::half::f16;
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &f16, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(value.to_f64())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f16, D::Error> {
+		f64::deserialize(deserializer).map(f16::from_f64)
+	}
+}
+
+/// Stores `half::f16` as 2 byte little-endian `BLOB`
+pub mod f16_blob {
+	use std::fmt;
+
+	use ::half::f16;
+	use serde::de::{self, Visitor};
+	use serde::{Deserializer, Serializer};
+
+	struct BitsVisitor;
+
+	impl Visitor<'_> for BitsVisitor {
+		type Value = f16;
+
+		fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+			formatter.write_str("BLOB of 2 bytes for f16")
+		}
+
+		fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+			v.try_into()
+				.map(f16::from_le_bytes)
+				.map_err(|_| E::invalid_length(v.len(), &self))
+		}
+	}
+
+	pub fn serialize<S: Serializer>(value: &f16, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&value.to_le_bytes())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f16, D::Error> {
+		deserializer.deserialize_byte_buf(BitsVisitor)
+	}
+}
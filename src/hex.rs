@@ -0,0 +1,48 @@
+//! Helpers to store `Vec<u8>` as hexadecimal `TEXT`
+//!
+//! Bytes are encoded as lowercase hex digits, two per byte. Both lowercase and uppercase digits (e.g. produced by SQLite
+//! `hex()` function) are accepted during deserialization, the odd-length text and non-hex characters produce an error.
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::hex")]
+//!    digest: Vec<u8>,
+//! }
+//! ```
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+	let mut out = String::with_capacity(value.len() * 2);
+	for byte in value {
+		out.push(char::from(DIGITS[usize::from(byte >> 4)]));
+		out.push(char::from(DIGITS[usize::from(byte & 0xf)]));
+	}
+	serializer.serialize_str(&out)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+	let text = String::deserialize(deserializer)?;
+	let digits = text
+		.chars()
+		.map(|digit| {
+			digit
+				.to_digit(16)
+				.map(|val| val as u8)
+				.ok_or_else(|| de::Error::custom(format_args!("invalid hex digit '{}' in \"{}\"", digit, text)))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	if digits.len() % 2 != 0 {
+		return Err(de::Error::custom(format_args!(
+			"hex text has odd length {}: \"{}\"",
+			digits.len(),
+			text
+		)));
+	}
+	Ok(digits.chunks_exact(2).map(|pair| pair[0] << 4 | pair[1]).collect())
+}
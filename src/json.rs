@@ -0,0 +1,60 @@
+//! Helpers for the columns storing JSON as `TEXT`
+
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{Row, RowIndex};
+use serde::de::DeserializeOwned;
+
+use crate::{Error, Result};
+
+/// Parses JSON stored in the `TEXT` column `idx` (index or name) of `row` into an instance of `D: serde::Deserialize`
+///
+/// JSON parsing errors are returned as `Error::Deserialization` with the column name, non-`TEXT` values produce
+/// `Error::TypeMismatch`.
+pub fn from_json_column<D: DeserializeOwned>(row: &Row, idx: impl RowIndex) -> Result<D> {
+	let idx = idx.idx(row.as_ref())?;
+	let column = || row.as_ref().column_name(idx).ok().map(str::to_owned);
+	match row.get_ref(idx)? {
+		ValueRef::Text(text) => serde_json::from_slice(text).map_err(|e| Error::Deserialization {
+			column: column(),
+			row: None,
+			message: e.to_string(),
+		}),
+		val => Err(Error::TypeMismatch {
+			column: column(),
+			row: None,
+			expected: "TEXT",
+			found: val.data_type(),
+		}),
+	}
+}
+
+/// Parses JSON array stored in the `TEXT` column `idx` (index or name) of `row` into `Vec` of `D: serde::Deserialize`
+///
+/// Shortcut for `from_json_column::<Vec<D>>()`.
+#[inline]
+pub fn from_json_array_column<D: DeserializeOwned>(row: &Row, idx: impl RowIndex) -> Result<Vec<D>> {
+	from_json_column(row, idx)
+}
+
+/// Converts `row` into JSON object with `columns` as keys
+///
+/// Values are mapped according to their storage class: `NULL` to `null`, `INTEGER` and `REAL` to numbers (infinite `REAL`s
+/// to `null`), `TEXT` to strings and `BLOB` to strings with standard base64 encoding (with padding).
+pub fn row_to_json(row: &Row, columns: &[String]) -> Result<serde_json::Value> {
+	let mut out = serde_json::Map::with_capacity(columns.len());
+	crate::visit_row(row, columns, |column, value| {
+		out.insert(column.to_owned(), json_value(value));
+	})?;
+	Ok(serde_json::Value::Object(out))
+}
+
+fn json_value(value: ValueRef) -> serde_json::Value {
+	match value {
+		ValueRef::Null => serde_json::Value::Null,
+		ValueRef::Integer(val) => val.into(),
+		ValueRef::Real(val) => val.into(),
+		ValueRef::Text(val) => String::from_utf8_lossy(val).into(),
+		ValueRef::Blob(val) => base64::engine::general_purpose::STANDARD.encode(val).into(),
+	}
+}
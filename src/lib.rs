@@ -6,10 +6,13 @@
 //!
 //! Serialization of named bound arguments is only supported from `struct`s and `map`s because other
 //! serde types lack column name information. Likewise, serialization of positional bound arguments
-//! is only supported from `tuple`s, `sequence`s and primitive non-iterable types. In the latter case
-//! the result will be single-element vector. Each serialized field or element must implement
+//! is only supported from `tuple`s, `sequence`s, `struct`s (field values in the declaration order), `map`s (values in the
+//! iteration order) and primitive non-iterable types. In the latter case the result will be single-element vector. Each serialized field or element must implement
 //! `rusqlite::types::ToSql`.
 //!
+//! Named bound arguments are produced in the order of `struct` fields or in the iteration order of the map. Prefer `BTreeMap`
+//! over `HashMap` when a deterministic (sorted by name) order of the arguments matters.
+//!
 //! For deserialization you can use two families of functions: `from_*()` and `from_*_with_columns()`.
 //! The most used one is the former. The latter allows you to specify column names for types that need
 //! them, but don't supply them. This includes different `Map` types like `HashMap`. Specifying columns
@@ -21,7 +24,10 @@
 //!
 //! Some types employ non-trivial handling, these are described below:
 //!
-//! * Serialization of `u64` will fail if it can't be represented by `i64` due to sqlite limitations.
+//! * Serialization of `u64` will fail if it can't be represented by `i64` due to sqlite limitations. Use
+//!   `SerializeOptions::u64_as_text()` to store such values as `TEXT` instead, `u64` is deserialized from both.
+//! * `i128` and `u128` are serialized as `TEXT` with the decimal representation of the number to be stored losslessly.
+//!   They are deserialized from such `TEXT` as well as from `INTEGER`.
 //! * Simple `enum`s will be serialized as strings so:
 //!
 //!   ```
@@ -32,18 +38,35 @@
 //!   ```
 //!
 //!   will have two possible `TEXT` options in the database "M" and "F". Deserialization into `enum`
-//!   from `TEXT` is also supported.
+//!   from `TEXT` is also supported, as well as from `INTEGER` that is used as the index of the variant in the declaration
+//!   order (0 for "M" and 1 for "F" above). To store explicit discriminants like `Ok = 200` use the `discriminant` module.
 //! * `bool`s are serialized as `INTEGER`s 0 or 1, can be deserialized from `INTEGER` and `REAL` where
 //!   0 and 0.0 are `false`, anything else is `true`.
 //! * `f64` and `f32` values of `NaN` are serialized as `NULL`s. When deserializing such value `Option<f64>`
 //!   will have value of `None` and `f64` will have value of `NaN`. The same applies to `f32`.
+//!   To avoid binary rounding of `REAL`s floats can also be stored as `TEXT` with `SerializeOptions::float_as_text()` and
+//!   read back with `DeserializeOptions::float_from_text()`.
 //! * `Bytes`, `ByteBuf` from `serde_bytes` are supported as optimized way of handling `BLOB`s.
 //! * `unit` serializes to `NULL`.
 //! * Only `sequence`s of `u8` are serialized and deserialized, `BLOB` database type is used. It's
-//!   more optimal though to use `Bytes` and `ByteBuf` from `serde_bytes` for such fields.
+//!   more optimal though to use `Bytes` and `ByteBuf` from `serde_bytes` for such fields. Fixed-size arrays like
+//!   `[u8; 32]` are stored as `BLOB`s too, deserialization fails if the `BLOB` length doesn't match the array length.
 //! * `unit_struct` serializes to `struct` name as `TEXT`, when deserializing the check is made to ensure
 //!   that `struct` name coincides with the string in the database.
 //!
+//! # Optional features
+//!
+//! * `blob` - enables the `blob` module with `blob_param_from_reader()` to stream large `BLOB`s into the database without
+//!   keeping them in memory.
+//! * `chrono` - enables the `chrono` module with `#[serde(with = "...")]` helpers for `chrono` date and time types.
+//! * `half` - enables the `half` module with `#[serde(with = "...")]` helpers for `half::f16` half-precision floats.
+//! * `net` - enables the `net` module with `#[serde(with = "...")]` helpers storing IP addresses as `BLOB`s.
+//! * `serde_json` - enables the `json` module with helpers for the columns storing JSON and `row_to_json()` to convert
+//!   whole rows. It also adds `SerializeOptions::compound_as_json()` to store the values that don't map to a single
+//!   SQLite value (`map`s, `struct`s, `enum`s with data, sequences and tuples) as JSON `TEXT` instead of producing an
+//!   error. Such `TEXT` is parsed back during deserialization into the types that expect these shapes, to read it into
+//!   `serde_json::Value` fields as objects and arrays enable `DeserializeOptions::parse_json_text()`.
+//!
 //! # Examples
 //! ```
 //! use serde_derive::{Deserialize, Serialize};
@@ -100,18 +123,49 @@
 //! assert_eq!(from_row::<Example>(&rows.next().unwrap().unwrap()).unwrap(), Example { id: 2, name: "second name".into() });
 //! ```
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+#[cfg(feature = "blob")]
+pub use blob::blob_param_from_reader;
 pub use rusqlite;
+use rusqlite::types::{Value, ValueRef};
 use rusqlite::{params_from_iter, ParamsFromIter};
 
-pub use de::{DeserRows, DeserRowsRef, RowDeserializer};
+pub use de::{
+	BlobRepresentation, DeserRows, DeserRowsFiltered, DeserRowsRef, DeserRowsWithRowid, DeserializeOptions, Deserialized,
+	RowDeserializer,
+};
 pub use error::{Error, Result};
-pub use ser::{NamedParamSlice, NamedSliceSerializer, PositionalParams, PositionalSliceSerializer};
+#[cfg(feature = "serde_json")]
+pub use json::{from_json_array_column, from_json_column, row_to_json};
+pub use query::{DeserStatementRows, QueryAs};
+pub use ser::{
+	FieldNamesSerializer, LazyParams, NamedParamSlice, NamedSliceSerializer, PositionalParams, PositionalSliceSerializer,
+	SerializeOptions, Serialized,
+};
 
+#[cfg(feature = "blob")]
+pub mod blob;
+#[cfg(feature = "chrono")]
+pub mod chrono;
 pub mod de;
+pub mod discriminant;
+pub mod empty_enum;
 pub mod error;
+#[cfg(feature = "half")]
+pub mod half;
+pub mod hex;
+#[cfg(feature = "serde_json")]
+pub mod json;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod null_if;
+mod query;
 pub mod ser;
 #[cfg(test)]
 mod tests;
+pub mod text_null;
 
 /// Returns column names of the statement the way `from_row_with_columns()` method expects them
 ///
@@ -137,6 +191,37 @@ pub fn from_row<D: serde::de::DeserializeOwned>(row: &rusqlite::Row) -> Result<D
 	from_row_with_columns(row, &columns_ref)
 }
 
+/// Deserializes an instance of `D: serde::Deserialize` that borrows from `rusqlite::Row`
+///
+/// Unlike `from_row()` the result can contain `&str` and `&[u8]` (or `serde_bytes::Bytes`) fields pointing directly into the
+/// `TEXT` and `BLOB` values of the row, so large columns are not copied. `Vec<u8>` and `serde_bytes::ByteBuf` fields still
+/// receive owned copies. The borrowed data is only valid until the row is advanced: the `Row` returned from
+/// `Rows::next()` borrows the `Rows`, so the compiler doesn't allow keeping the result after the next call to `next()`.
+/// Convert the needed values to owned ones before that.
+///
+/// ```
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Named<'a> {
+///    id: i64,
+///    name: &'a str,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let mut statement = connection.prepare("SELECT 1 AS id, 'first' AS name").unwrap();
+/// let mut rows = statement.query([]).unwrap();
+/// let row = rows.next().unwrap().unwrap();
+/// let res = serde_rusqlite::from_row_borrowed::<Named>(row).unwrap();
+/// assert_eq!(res, Named { id: 1, name: "first" });
+/// ```
+#[inline]
+pub fn from_row_borrowed<'row, D: serde::Deserialize<'row>>(row: &'row rusqlite::Row) -> Result<D> {
+	let columns = row.as_ref().column_names();
+	let columns_ref = columns.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+	D::deserialize(RowDeserializer::from_row_with_columns(row, &columns_ref))
+}
+
 /// Deserializes any instance of `D: serde::Deserialize` from `rusqlite::Row` with specified columns
 ///
 /// Use this function over `from_row()` to avoid allocation and overhead for fetching column names. To get columns names
@@ -150,7 +235,285 @@ pub fn from_row<D: serde::de::DeserializeOwned>(row: &rusqlite::Row) -> Result<D
 /// There will be 2 generic type arguments to the `from_row_with_columns()` instead of one.
 #[inline]
 pub fn from_row_with_columns<D: serde::de::DeserializeOwned>(row: &rusqlite::Row, columns: &[String]) -> Result<D> {
-	D::deserialize(RowDeserializer::from_row_with_columns(row, columns))
+	from_row_with_options(row, columns, &DeserializeOptions::default())
+}
+
+/// Deserializes any instance of `D: serde::Deserialize` from `rusqlite::Row` with specified columns and `options`
+///
+/// This is the single entry point for all the deserialization flags, combine them using the `DeserializeOptions` builder:
+///
+/// ```
+/// use serde_rusqlite::DeserializeOptions;
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let options = DeserializeOptions::default().integer_from_real(true).first_char(true);
+/// let mut statement = connection.prepare("SELECT 2.0, 'yes'").unwrap();
+/// let columns = serde_rusqlite::columns_from_statement(&statement);
+/// let res = statement
+///    .query_row([], |row| Ok(serde_rusqlite::from_row_with_options::<(i64, char)>(row, &columns, &options)))
+///    .unwrap()
+///    .unwrap();
+/// assert_eq!(res, (2, 'y'));
+/// ```
+#[inline]
+pub fn from_row_with_options<D: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	columns: &[String],
+	options: &DeserializeOptions,
+) -> Result<D> {
+	D::deserialize(RowDeserializer::from_row_with_options(row, columns, *options))
+}
+
+/// Deserializes a pair of `A: serde::Deserialize` and `B: serde::Deserialize` from `rusqlite::Row` with specified columns
+///
+/// The first `split_at` columns are used for `A` and the rest for `B`, so e.g. the result of a `JOIN` can be split between
+/// the `struct`s for each of the joined tables even if the column names clash. Fails with `Error::Deserialization` if
+/// `split_at` is larger than the number of `columns`.
+pub fn from_row_split<A: serde::de::DeserializeOwned, B: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	columns: &[String],
+	split_at: usize,
+) -> Result<(A, B)> {
+	if split_at > columns.len() {
+		return Err(serde::de::Error::custom(format_args!(
+			"can't split {} columns at {}",
+			columns.len(),
+			split_at
+		)));
+	}
+	let (first, second) = columns.split_at(split_at);
+	let a = A::deserialize(RowDeserializer::from_row_with_columns(row, first))?;
+	let b = B::deserialize(RowDeserializer::from_row_with_columns(row, second).with_offset(split_at))?;
+	Ok((a, b))
+}
+
+/// Deserializes `D: serde::Deserialize` from `rusqlite::Row` and returns the columns it didn't consume alongside
+///
+/// Instead of being dropped (or rejected with `#[serde(deny_unknown_fields)]`) the values of the columns that `D` ignores
+/// are collected into the map by column name:
+///
+/// ```
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Example {
+///    id: i64,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let mut statement = connection.prepare("SELECT 1 AS id, 'first' AS name").unwrap();
+/// let columns = serde_rusqlite::columns_from_statement(&statement);
+/// let (res, extras) = statement
+///    .query_row([], |row| Ok(serde_rusqlite::from_row_with_extras::<Example>(row, &columns)))
+///    .unwrap()
+///    .unwrap();
+/// assert_eq!(res.id, 1);
+/// assert_eq!(extras["name"], rusqlite::types::Value::Text("first".into()));
+/// ```
+pub fn from_row_with_extras<D: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	columns: &[String],
+) -> Result<(D, HashMap<String, Value>)> {
+	let ignored = RefCell::new(vec![]);
+	let res = D::deserialize(RowDeserializer::from_row_with_columns(row, columns).with_ignored(&ignored))?;
+	let extras = ignored
+		.into_inner()
+		.into_iter()
+		.map(|idx| Ok((columns[idx].clone(), row.get(idx)?)))
+		.collect::<Result<_>>()?;
+	Ok((res, extras))
+}
+
+/// Boxed function that deserializes `rusqlite::Row` into `D: serde::Deserialize`, check `row_mapper()`
+pub type RowMapper<D> = Box<dyn Fn(&rusqlite::Row) -> Result<D> + Send + Sync>;
+
+/// Returns a function that deserializes rows into `D: serde::Deserialize` using the owned `columns`
+///
+/// This is `from_row_with_columns()` with the columns captured, so the mapper can be stored (e.g. in a `struct` field)
+/// and passed to `query_and_then()` without a closure.
+///
+/// ```
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let mut statement = connection.prepare("SELECT 1 AS id, 'first' AS name").unwrap();
+/// let mapper = serde_rusqlite::row_mapper::<(i64, String)>(serde_rusqlite::columns_from_statement(&statement));
+/// let row = statement.query_and_then([], &mapper).unwrap().next().unwrap().unwrap();
+/// assert_eq!(row, (1, "first".into()));
+/// ```
+pub fn row_mapper<D: serde::de::DeserializeOwned + 'static>(columns: Vec<String>) -> RowMapper<D> {
+	Box::new(move |row| from_row_with_columns(row, &columns))
+}
+
+/// Deserializes any instance of `D: serde::Deserialize` from `rusqlite::Row` using column indices instead of the names as
+/// the keys for maps
+///
+/// Useful for deserialization into e.g. `BTreeMap<usize, T>` when the column names are missing or duplicated. The indices
+/// start at 0. Column names are still used in the error messages. `struct`s can't be deserialized this way as their
+/// fields are always matched by name.
+pub fn from_row_by_index<D: serde::de::DeserializeOwned>(row: &rusqlite::Row) -> Result<D> {
+	let columns = columns_from_statement(row.as_ref());
+	D::deserialize(RowDeserializer::from_row_with_columns(row, &columns).with_index_keys())
+}
+
+/// Deserializes any instance of `D: serde::Deserialize` from `rusqlite::Row` with specified columns skipping the `ignore`
+/// columns
+///
+/// The ignored columns are not presented to `struct`s and maps at all, so unlike `#[serde(deny_unknown_fields)]` they
+/// don't produce an error and unlike the unknown fields they don't end up in maps or `#[serde(flatten)]` fields. A `struct`
+/// field with the name of an ignored column is treated as missing. Sequences and tuples still receive all the columns.
+pub fn from_row_with_columns_ignoring<D: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	columns: &[String],
+	ignore: &[&str],
+) -> Result<D> {
+	D::deserialize(RowDeserializer::from_row_with_columns(row, columns).with_skip_columns(ignore))
+}
+
+/// Deserializes any instance of `D: serde::Deserialize` from `rusqlite::Row` with specified columns renamed according to
+/// `field_map`
+///
+/// This is a runtime counterpart of `#[serde(rename)]`: each column found in the keys of `field_map` is presented to
+/// `D` under the corresponding value, other columns keep their names.
+pub fn from_row_with_field_map<D: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	columns: &[String],
+	field_map: &HashMap<String, String>,
+) -> Result<D> {
+	let columns = columns
+		.iter()
+		.map(|column| field_map.get(column).unwrap_or(column).clone())
+		.collect::<Vec<_>>();
+	from_row_with_columns(row, &columns)
+}
+
+/// Deserializes any instance of `D: serde::Deserialize` from the row assembled in memory as pairs of column name and value
+///
+/// The values are handled exactly the same way as the ones coming from `rusqlite::Row` in `from_row()`, so this is useful
+/// for testing of the deserialization without a database and for processing of the rows that don't come from SQLite.
+pub fn from_value_row<D: serde::de::DeserializeOwned>(values: &[(String, Value)]) -> Result<D> {
+	let columns = values.iter().map(|(column, _)| column.clone()).collect::<Vec<_>>();
+	D::deserialize(RowDeserializer::from_values_with_options(
+		values,
+		&columns,
+		DeserializeOptions::default(),
+	))
+}
+
+/// Calls `visitor` for each of the `columns` with the column name and its raw value borrowed from `rusqlite::Row`
+///
+/// This is a lower level alternative to deserialization for the schema-agnostic processing of the rows. `columns` are
+/// expected to be in the same order as in the row, just like for `from_row_with_columns()`.
+#[inline]
+pub fn visit_row(row: &rusqlite::Row, columns: &[String], mut visitor: impl FnMut(&str, ValueRef)) -> Result<()> {
+	for (idx, column) in columns.iter().enumerate() {
+		visitor(column, row.get_ref(idx)?);
+	}
+	Ok(())
+}
+
+/// Deserializes the `BLOB` column `idx` (index or name) of `row` that stores consecutive fixed-size records into `Vec` of
+/// `D: serde::Deserialize`
+///
+/// The blob is split into chunks of `record_size` bytes and each chunk is deserialized into separate `D`. Fields of `D` are
+/// read in order without padding: integers and floats are little-endian values of their natural size, `bool` is a single
+/// byte, arrays, tuples and nested `struct`s are read element by element. Variable-length types can't be used in such
+/// records. If `D` consumes less than `record_size` bytes the rest of the chunk is ignored, so padded records are also
+/// supported.
+///
+/// ```
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Sample {
+///    channel: u8,
+///    value: i16,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let res = connection.query_row("SELECT x'01ff00027f00'", [], |row| {
+///    Ok(serde_rusqlite::blob_records::<Sample>(row, 0, 3))
+/// }).unwrap().unwrap();
+/// assert_eq!(res, [Sample { channel: 1, value: 255 }, Sample { channel: 2, value: 127 }]);
+/// ```
+pub fn blob_records<D: serde::de::DeserializeOwned>(
+	row: &rusqlite::Row,
+	idx: impl rusqlite::RowIndex,
+	record_size: usize,
+) -> Result<Vec<D>> {
+	let idx = idx.idx(row.as_ref())?;
+	let column = || row.as_ref().column_name(idx).ok().map(str::to_owned);
+	let blob = match row.get_ref(idx)? {
+		ValueRef::Blob(blob) => blob,
+		val => {
+			return Err(Error::TypeMismatch {
+				column: column(),
+				row: None,
+				expected: "BLOB",
+				found: val.data_type(),
+			})
+		}
+	};
+	if record_size == 0 || blob.len() % record_size != 0 {
+		return Err(Error::Deserialization {
+			column: column(),
+			row: None,
+			message: format!(
+				"BLOB of {} byte(s) can't be split into records of {} byte(s)",
+				blob.len(),
+				record_size
+			),
+		});
+	}
+	blob
+		.chunks_exact(record_size)
+		.map(|record| {
+			D::deserialize(&mut de::PackedDeserializer::new(record)).map_err(|e| match e {
+				Error::Deserialization { message, .. } => Error::Deserialization {
+					column: column(),
+					row: None,
+					message,
+				},
+				e => e,
+			})
+		})
+		.collect()
+}
+
+/// Deserializes all records from `rusqlite::Rows` into a single instance of `D: serde::Deserialize` column by column
+///
+/// This produces a columnar (struct-of-arrays) layout instead of the row-oriented one: each column is presented as a field
+/// containing the sequence of its values from every row, so `D` is expected to be a `struct` of `Vec`s (or a map of
+/// collections). The values are handled the same way as in `from_row()`. All rows are read into memory first.
+///
+/// ```
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Columns {
+///    id: Vec<i64>,
+///    name: Vec<String>,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let mut statement = connection.prepare("SELECT 1 AS id, 'first' AS name UNION ALL SELECT 2, 'second'").unwrap();
+/// let res = serde_rusqlite::from_rows_columnar::<Columns>(statement.query([]).unwrap()).unwrap();
+/// assert_eq!(res, Columns { id: vec![1, 2], name: vec!["first".into(), "second".into()] });
+/// ```
+pub fn from_rows_columnar<D: serde::de::DeserializeOwned>(mut rows: rusqlite::Rows) -> Result<D> {
+	let columns = rows
+		.as_ref()
+		.map(columns_from_statement)
+		.ok_or(Error::ColumnNamesNotAvailable)?;
+	let mut values = vec![vec![]; columns.len()];
+	while let Some(row) = rows.next()? {
+		for (idx, column) in values.iter_mut().enumerate() {
+			column.push(row.get::<_, Value>(idx)?);
+		}
+	}
+	D::deserialize(de::ColumnarDeserializer::new(
+		&columns,
+		&values,
+		DeserializeOptions::default(),
+	))
 }
 
 /// Returns iterator that owns `rusqlite::Rows` and deserializes all records from it into instances of `D: serde::Deserialize`
@@ -174,13 +537,144 @@ pub fn from_rows_ref<'rows, 'stmt, D: serde::de::DeserializeOwned>(
 	DeserRowsRef::new(rows)
 }
 
+/// Deserializes all records from `rusqlite::Rows` into `Vec` of `D: serde::Deserialize`
+///
+/// `estimated_len` is used to preallocate the resulting `Vec`, e.g. pass `n` for a `SELECT ... LIMIT n` query. It's only a
+/// hint, the `Vec` grows as usual if there are more rows. The first deserialization error stops the process and is returned.
+pub fn from_rows_vec<D: serde::de::DeserializeOwned>(rows: rusqlite::Rows, estimated_len: usize) -> Result<Vec<D>> {
+	let mut out = Vec::with_capacity(estimated_len);
+	for record in from_rows(rows) {
+		out.push(record?);
+	}
+	Ok(out)
+}
+
+/// Returns iterator that owns `rusqlite::Rows` and deserializes only the records that match `predicate` into instances of
+/// `D: serde::Deserialize`
+///
+/// `predicate` receives the raw `rusqlite::Row` before any deserialization is done, so the rows that are skipped don't incur
+/// conversion costs.
+#[inline]
+pub fn from_rows_filtered<D: serde::de::DeserializeOwned, F: FnMut(&rusqlite::Row) -> bool>(
+	rows: rusqlite::Rows,
+	predicate: F,
+) -> DeserRowsFiltered<D, F> {
+	DeserRowsFiltered::new(rows, predicate)
+}
+
+/// Returns iterator that owns `rusqlite::Rows` and deserializes all records from it into pairs of `rowid` and instances of
+/// `D: serde::Deserialize`
+///
+/// If the first column of the query is named `rowid` (or one of its aliases `oid` and `_rowid_`, case-insensitive) it's read
+/// as the id and the remaining columns are deserialized into `D`, so `SELECT rowid, * FROM table` works as expected. Otherwise,
+/// the first column is considered to be an alias for `rowid` (e.g. `INTEGER PRIMARY KEY` column), it's read as the id and is
+/// also passed to `D` together with the rest of the columns.
+///
+/// Tables created `WITHOUT ROWID` don't have the implicit column so selecting it fails during statement preparation. If the
+/// first column doesn't contain an `INTEGER` an error is returned for every row.
+#[inline]
+pub fn from_rows_with_rowid<D: serde::de::DeserializeOwned>(rows: rusqlite::Rows) -> DeserRowsWithRowid<D> {
+	DeserRowsWithRowid::new(rows)
+}
+
+/// Prepares `sql` on `conn`, binds serialized `params` and returns iterator deserializing the resulting rows into instances
+/// of `D: serde::Deserialize`
+///
+/// Free function form of `QueryAs::query_as()`, check it for the details on parameter binding. The returned iterator owns
+/// the prepared statement, so it can be returned from a function or stored as long as `conn` outlives it, but the statement
+/// is prepared anew on every call. In the loops prefer `Connection::prepare_cached()` with `from_rows()` to reuse it. Call
+/// `.collect::<Result<Vec<_>>>()` on the result to release the connection borrow immediately.
+///
+/// ```
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection.execute_batch("CREATE TABLE example (id INT, name TEXT); INSERT INTO example VALUES (1, 'first')").unwrap();
+/// let res = serde_rusqlite::query_deserialize::<(i64, String), _>(&connection, "SELECT * FROM example WHERE id = ?", (1,))
+///    .unwrap()
+///    .collect::<serde_rusqlite::Result<Vec<_>>>()
+///    .unwrap();
+/// assert_eq!(res, [(1, "first".to_string())]);
+/// ```
+#[inline]
+pub fn query_deserialize<'conn, D: serde::de::DeserializeOwned, P: serde::Serialize>(
+	conn: &'conn rusqlite::Connection,
+	sql: &str,
+	params: P,
+) -> Result<DeserStatementRows<'conn, D>> {
+	conn.query_as(sql, params)
+}
+
 /// Serializes an instance of `S: serde::Serialize` into structure for positional bound query arguments
 ///
 /// To get the slice suitable for supplying to `query()` or `execute()` call `to_slice()` on the `Ok` result and
 /// borrow it.
+///
+/// Primitive values, `()` and `None` produce a single argument (the latter two a `NULL`), while empty sequences and tuples
+/// produce no arguments. Check `SerializeOptions::error_on_empty()` to turn the latter case into an error.
+///
+/// Top-level sequences, tuples and fixed-size arrays produce an argument per element, so `to_params([1, 2, 3])` binds
+/// `?1`, `?2` and `?3`. Arrays and `Vec`s of `u8` are indistinguishable from a tuple of `u8`s at this level, so to bind
+/// them as a single `BLOB` argument wrap them into `ser::Blob`, e.g. `to_params(Blob([1_u8, 2, 3]))`.
+///
+/// `struct`s produce an argument per field in the declaration order ignoring the field names, so they can be used with
+/// `VALUES(?, ?, ...)` statements. Fields skipped with `#[serde(skip_serializing_if = "...")]` produce no argument and
+/// shift the positions of the following ones.
+///
+/// `map`s produce an argument per value in the iteration order of the map ignoring the keys. That order is whatever the
+/// map type yields, so use ordered maps like `BTreeMap` here, the order of `HashMap` is unspecified and changes between
+/// runs.
+///
+/// Use `to_value_vec()` to get the same arguments as owned `Value`s, e.g. for logging.
 #[inline]
 pub fn to_params<S: serde::Serialize>(obj: S) -> Result<ParamsFromIter<PositionalParams>> {
-	obj.serialize(PositionalSliceSerializer::default()).map(params_from_iter)
+	to_params_with_options(obj, &SerializeOptions::default())
+}
+
+/// Serializes an instance of `S: serde::Serialize` into structure for positional bound query arguments using `options`
+///
+/// This is the single entry point for all the serialization flags, combine them using the `SerializeOptions` builder.
+#[inline]
+pub fn to_params_with_options<S: serde::Serialize>(
+	obj: S,
+	options: &SerializeOptions,
+) -> Result<ParamsFromIter<PositionalParams>> {
+	obj.serialize(PositionalSliceSerializer::with_options(*options))
+		.map(params_from_iter)
+}
+
+/// Serializes an instance of `S: serde::Serialize` into positional bound query arguments ordered to match the named
+/// parameters of `stmt`
+///
+/// `obj` is serialized the same way as with `to_params_named()`, then the values are ordered according to the parameters
+/// of the statement matching them by name, the prefix of the parameter (`:`, `@` or `$`) is ignored. Fields that are not
+/// used by the statement are skipped. It's an error if the statement has an anonymous parameter (`?`) or a parameter that
+/// has no corresponding field.
+pub fn to_params_for_statement<S: serde::Serialize>(
+	stmt: &rusqlite::Statement,
+	obj: S,
+) -> Result<ParamsFromIter<PositionalParams>> {
+	let mut named = to_params_named(obj)?;
+	let mut out = PositionalParams::with_capacity(stmt.parameter_count());
+	for idx in 1..=stmt.parameter_count() {
+		let name = stmt
+			.parameter_name(idx)
+			.ok_or_else(|| Error::Serialization(format!("Statement parameter {} is anonymous", idx)))?;
+		let pos = named
+			.iter()
+			.position(|(field, _)| field[1..] == name[1..])
+			.ok_or_else(|| Error::Serialization(format!("No value for statement parameter: {}", name)))?;
+		out.push(named.swap_remove(pos).1);
+	}
+	Ok(params_from_iter(out))
+}
+
+/// Serializes an instance of `S: serde::Serialize` into positional bound query arguments lazily, one by one, as rusqlite
+/// binds them
+///
+/// The result can be passed directly to `query()` or `execute()`. Serialization errors are reported by rusqlite as
+/// `ToSqlConversionFailure`. Check `LazyParams` for the performance tradeoffs compared to `to_params()`.
+#[inline]
+pub fn to_params_lazy<S: serde::Serialize>(obj: S) -> ParamsFromIter<LazyParams<S>> {
+	params_from_iter(LazyParams::new(obj, SerializeOptions::default()))
 }
 
 /// Serializes an instance of `S: serde::Serialize` into structure for named bound query arguments
@@ -189,7 +683,78 @@ pub fn to_params<S: serde::Serialize>(obj: S) -> Result<ParamsFromIter<Positiona
 /// and borrow it.
 #[inline]
 pub fn to_params_named<S: serde::Serialize>(obj: S) -> Result<NamedParamSlice> {
-	obj.serialize(NamedSliceSerializer::default())
+	to_params_named_with_options(obj, &SerializeOptions::default())
+}
+
+/// Serializes an instance of `S: serde::Serialize` into structure for named bound query arguments using `options`
+///
+/// This is the single entry point for all the serialization flags, combine them using the `SerializeOptions` builder:
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_rusqlite::SerializeOptions;
+///
+/// #[derive(Serialize)]
+/// enum Status {
+///    Active,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Update {
+///    status: Status,
+///    note: Option<String>,
+/// }
+///
+/// let options = SerializeOptions::default().skip_none(true).enum_as_index(true);
+/// let params = serde_rusqlite::to_params_named_with_options(Update { status: Status::Active, note: None }, &options).unwrap();
+/// assert_eq!(params.len(), 1);
+/// assert_eq!(params[0].0, ":status");
+/// ```
+#[inline]
+pub fn to_params_named_with_options<S: serde::Serialize>(obj: S, options: &SerializeOptions) -> Result<NamedParamSlice> {
+	obj.serialize(NamedSliceSerializer::with_options(*options))
+}
+
+/// Serializes an instance of `S: serde::Serialize` into structure for named bound query arguments with names starting
+/// with `prefix`
+///
+/// Use it for the statements with `@name` or `$name` parameters. Fails with `Error::Serialization` if `prefix` is not one
+/// of `:`, `@` or `$`.
+#[inline]
+pub fn to_params_named_with_prefix<S: serde::Serialize>(obj: S, prefix: char) -> Result<NamedParamSlice> {
+	obj.serialize(NamedSliceSerializer::with_prefix(prefix)?)
+}
+
+/// Serializes each item of `iter` into structure for named bound query arguments
+///
+/// Repeating named parameters in a single statement (like `VALUES (:a, :b), (:a, :b)`) binds the same value, so to insert
+/// multiple rows prepare the statement once and execute it for each of the results:
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_rusqlite::*;
+///
+/// #[derive(Serialize)]
+/// struct Example {
+///    id: i64,
+///    name: String,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection.execute("CREATE TABLE example (id INT, name TEXT)", []).unwrap();
+/// let rows = [Example { id: 1, name: "first".into() }, Example { id: 2, name: "second".into() }];
+/// let mut statement = connection.prepare("INSERT INTO example (id, name) VALUES (:id, :name)").unwrap();
+/// for params in to_params_named_each(&rows).unwrap() {
+///    statement.execute(params.to_slice().as_slice()).unwrap();
+/// }
+/// ```
+#[inline]
+pub fn to_params_named_each<I>(iter: I) -> Result<Vec<NamedParamSlice>>
+where
+	I: IntoIterator,
+	I::Item: serde::Serialize,
+{
+	iter.into_iter().map(to_params_named).collect()
 }
 
 /// Serializes only the specified `fields` of an instance of `S: serde::Serialize` into structure
@@ -201,3 +766,356 @@ pub fn to_params_named<S: serde::Serialize>(obj: S) -> Result<NamedParamSlice> {
 pub fn to_params_named_with_fields<S: serde::Serialize>(obj: S, fields: &[&str]) -> Result<NamedParamSlice> {
 	obj.serialize(NamedSliceSerializer::with_only_fields(fields))
 }
+
+/// Serializes an instance of `S: serde::Serialize` into structure for named bound query arguments omitting the `NULL`s
+///
+/// Shortcut for `to_params_named_with_options()` with `SerializeOptions::skip_none()` set, useful to build dynamic
+/// `UPDATE` statements that only touch the fields that are present. Note that the set of `:name` parameters then depends
+/// on the values, so the statement text must be built from the result (e.g. with `named_set_clause()`) rather than
+/// prepared once for all the values.
+#[inline]
+pub fn to_params_named_skip_none<S: serde::Serialize>(obj: S) -> Result<NamedParamSlice> {
+	to_params_named_with_options(obj, &SerializeOptions::default().skip_none(true))
+}
+
+/// Serializes only the specified `fields` of an instance of `S: serde::Serialize` that are not `None` into structure for
+/// named bound query arguments
+///
+/// Combines `to_params_named_with_fields()` with `SerializeOptions::skip_none()`, useful for partial updates of the
+/// records, e.g. to produce `SET` clause for only the present values of the specified fields.
+#[inline]
+pub fn to_params_named_with_fields_skip_none<S: serde::Serialize>(obj: S, fields: &[&str]) -> Result<NamedParamSlice> {
+	obj.serialize(NamedSliceSerializer::with_only_fields_and_options(
+		fields,
+		SerializeOptions::default().skip_none(true),
+	))
+}
+
+/// Returns the names of the fields of a `struct` or the keys of a map `S: serde::Serialize` in the serialization order
+///
+/// The values are not serialized at all, so they can be of any type. The names are the same as the ones produced by
+/// `to_params_named()` without the prefix, including the effect of `#[serde(rename)]` and `#[serde(flatten)]`, which makes
+/// this function handy for assembling the column list of an `INSERT`. Fields skipped with `#[serde(skip_serializing_if)]`
+/// are not included. Other types fail with `Error::Unsupported`.
+///
+/// ```
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Example {
+///    id: i64,
+///    name: String,
+/// }
+///
+/// let example = Example { id: 1, name: "first".into() };
+/// let columns = serde_rusqlite::field_names(&example).unwrap();
+/// let sql = format!("INSERT INTO example ({}) VALUES (:{})", columns.join(", "), columns.join(", :"));
+/// assert_eq!(sql, "INSERT INTO example (id, name) VALUES (:id, :name)");
+/// ```
+pub fn field_names<S: serde::Serialize>(obj: &S) -> Result<Vec<String>> {
+	obj.serialize(FieldNamesSerializer::default())
+}
+
+/// Builds the list of `column = :column` assignments for `UPDATE ... SET` clause from the named bound query arguments
+///
+/// The column names are the argument names without the leading `:`, `@` or `$`, the assignments are separated by `, `.
+/// Returns an empty string for an empty `params`, which is not a valid `SET` clause, so check for that when the arguments
+/// can be empty (e.g. after `to_params_named_with_fields_skip_none()`).
+///
+/// ```
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Rename {
+///    name: String,
+///    title: String,
+/// }
+///
+/// let params = serde_rusqlite::to_params_named(Rename { name: "a".into(), title: "b".into() }).unwrap();
+/// let sql = format!("UPDATE example SET {} WHERE id = 1", serde_rusqlite::named_set_clause(&params));
+/// assert_eq!(sql, "UPDATE example SET name = :name, title = :title WHERE id = 1");
+/// ```
+pub fn named_set_clause(params: &NamedParamSlice) -> String {
+	params
+		.iter()
+		.map(|(name, _)| format!("{} = {}", strip_param_prefix(name), name))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// Serializes an instance of `S: serde::Serialize` into named bound query arguments together with the upsert clause
+///
+/// The clause is `ON CONFLICT(...) DO UPDATE SET ...` with the `keys` as the conflict target and the assignments of the
+/// rest of the fields from `excluded`. If all the fields are in `keys` `DO NOTHING` is used instead. The column names are
+/// quoted. Fails with `Error::Serialization` if `keys` is empty or some of them are not among the serialized fields.
+///
+/// ```
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Example {
+///    id: i64,
+///    name: String,
+/// }
+///
+/// let (params, upsert) = serde_rusqlite::upsert_fragment(Example { id: 1, name: "first".into() }, &["id"]).unwrap();
+/// let sql = format!(
+///    "INSERT INTO example (id, name) VALUES {} {}",
+///    serde_rusqlite::named_placeholders(&params),
+///    upsert
+/// );
+/// assert_eq!(
+///    sql,
+///    r#"INSERT INTO example (id, name) VALUES (:id, :name) ON CONFLICT("id") DO UPDATE SET "name" = excluded."name""#
+/// );
+/// ```
+pub fn upsert_fragment<S: serde::Serialize>(obj: S, keys: &[&str]) -> Result<(NamedParamSlice, String)> {
+	if keys.is_empty() {
+		return Err(Error::Serialization("upsert requires at least one key column".to_string()));
+	}
+	let params = to_params_named(obj)?;
+	let columns = params.iter().map(|(name, _)| strip_param_prefix(name)).collect::<Vec<_>>();
+	if let Some(key) = keys.iter().find(|key| !columns.contains(key)) {
+		return Err(Error::Serialization(format!("upsert key column is not serialized: {}", key)));
+	}
+	let assignments = columns
+		.iter()
+		.filter(|column| !keys.contains(column))
+		.map(|column| {
+			let column = quote_identifier(column);
+			format!("{} = excluded.{}", column, column)
+		})
+		.collect::<Vec<_>>();
+	let action = if assignments.is_empty() {
+		"DO NOTHING".to_string()
+	} else {
+		format!("DO UPDATE SET {}", assignments.join(", "))
+	};
+	let keys = keys.iter().map(|key| quote_identifier(key)).collect::<Vec<_>>();
+	let fragment = format!("ON CONFLICT({}) {}", keys.join(", "), action);
+	Ok((params, fragment))
+}
+
+/// Builds the parenthesized list of `?` placeholders for `VALUES` clause, one for each positional bound query argument
+///
+/// Returns `()` for an empty `params`.
+///
+/// ```
+/// let params = serde_rusqlite::PositionalSliceSerializer::default();
+/// let params = serde::Serialize::serialize(&(1, "name", 2.5), params).unwrap();
+/// let sql = format!("INSERT INTO example VALUES {}", serde_rusqlite::positional_placeholders(&params));
+/// assert_eq!(sql, "INSERT INTO example VALUES (?, ?, ?)");
+/// ```
+pub fn positional_placeholders(params: &PositionalParams) -> String {
+	format!("({})", vec!["?"; params.len()].join(", "))
+}
+
+/// Builds the parenthesized list of the named placeholders for `VALUES` clause from the named bound query arguments
+///
+/// The placeholders are the argument names including the prefix, e.g. `(:id, :name)`, in the order of the arguments.
+/// Returns `()` for an empty `params`.
+pub fn named_placeholders(params: &NamedParamSlice) -> String {
+	format!(
+		"({})",
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+	)
+}
+
+/// Serializes an instance of `S: serde::Serialize` into `(name, value)` pairs, the in-memory counterpart of
+/// `to_params_named()`
+///
+/// The names don't have the leading `:` of the bound query arguments, so the result can be passed directly to
+/// `from_value_row()`.
+pub fn to_value_row<S: serde::Serialize>(obj: S) -> Result<Vec<(String, Value)>> {
+	to_named_values_filtered(obj, |_, _| true)
+}
+
+/// Serializes an instance of `S: serde::Serialize` into `(name, value)` pairs exactly as `to_params_named()` binds them
+///
+/// Unlike `to_value_row()` the names keep the leading `:`, so the result is a `Debug`-printable and comparable copy of the
+/// named bound query arguments, e.g. for snapshot tests or audit logs of the writes:
+///
+/// ```
+/// use rusqlite::types::Value;
+///
+/// let values = serde_rusqlite::to_values_named(std::collections::BTreeMap::from([("id", 1)])).unwrap();
+/// assert_eq!(values, [(":id".to_string(), Value::Integer(1))]);
+/// ```
+#[inline]
+pub fn to_values_named<S: serde::Serialize>(obj: S) -> Result<Vec<(String, Value)>> {
+	to_values_named_with_options(obj, &SerializeOptions::default())
+}
+
+/// Serializes an instance of `S: serde::Serialize` into `(name, value)` pairs exactly as `to_params_named_with_options()`
+/// binds them
+///
+/// The names start with the prefix set by `SerializeOptions::named_prefix()`.
+pub fn to_values_named_with_options<S: serde::Serialize>(obj: S, options: &SerializeOptions) -> Result<Vec<(String, Value)>> {
+	to_params_named_with_options(obj, options)?
+		.iter()
+		.map(|(name, param)| Ok((name.clone(), ser::param_to_value(param.as_ref())?)))
+		.collect()
+}
+
+/// Serializes an instance of `S: serde::Serialize` into `Vec` of values, the in-memory counterpart of `to_params()`
+///
+/// The values are exactly the ones that `to_params()` binds, but unlike `PositionalParams` they can be `Debug`-printed
+/// and compared, so use this to log the query arguments or to check the serialization in tests without a connection:
+///
+/// ```
+/// use rusqlite::types::Value;
+///
+/// let values = serde_rusqlite::to_value_vec((1, "two", None::<f64>)).unwrap();
+/// assert_eq!(values, [Value::Integer(1), Value::Text("two".into()), Value::Null]);
+/// ```
+pub fn to_value_vec<S: serde::Serialize>(obj: S) -> Result<Vec<Value>> {
+	obj.serialize(PositionalSliceSerializer::default())?
+		.iter()
+		.map(|param| ser::param_to_value(param.as_ref()))
+		.collect()
+}
+
+/// Serializes an instance of `S: serde::Serialize` into `(name, value)` pairs keeping only those that satisfy `predicate`
+///
+/// The names don't have the leading `:` of the bound query arguments. `predicate` receives the name and the value that would
+/// be bound for it, this is useful e.g. to collect only the non-`NULL` fields or only the fields that differ from the
+/// previous state of the record.
+pub fn to_named_values_filtered<S: serde::Serialize>(
+	obj: S,
+	mut predicate: impl FnMut(&str, &Value) -> bool,
+) -> Result<Vec<(String, Value)>> {
+	let mut out = vec![];
+	for (name, param) in to_params_named(obj)?.iter() {
+		let name = strip_param_prefix(name);
+		let value = ser::param_to_value(param.as_ref())?;
+		if predicate(name, &value) {
+			out.push((name.to_owned(), value));
+		}
+	}
+	Ok(out)
+}
+
+/// Inserts all `rows` into `table` filling the specified `columns` and returns the total number of inserted rows
+///
+/// The `INSERT` statement with named parameters for `columns` is prepared once and each row is serialized with
+/// `to_params_named_with_fields()`, so the rows must be `struct`s or maps. Columns that are missing from a row are bound
+/// as `NULL`. The table and column names are quoted, but the column names are also used as the parameter names, so they
+/// must consist of ASCII letters, digits and `_` only, otherwise `Error::Serialization` is returned.
+///
+/// If `transaction` is set all the rows are inserted within a single transaction that is rolled back on the first error,
+/// so either all or none of the rows are inserted. Otherwise the rows inserted before the error stay in the table. Note
+/// that a transaction can't be started when there is one already active on the `connection`.
+///
+/// ```
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Example {
+///    id: i64,
+///    name: String,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection.execute("CREATE TABLE example (id INT, name TEXT)", []).unwrap();
+/// let rows = [Example { id: 1, name: "first".into() }, Example { id: 2, name: "second".into() }];
+/// let inserted = serde_rusqlite::insert_all(&connection, "example", &["id", "name"], &rows, true).unwrap();
+/// assert_eq!(inserted, 2);
+/// ```
+pub fn insert_all<S: serde::Serialize>(
+	connection: &rusqlite::Connection,
+	table: &str,
+	columns: &[&str],
+	rows: &[S],
+	transaction: bool,
+) -> Result<usize> {
+	if let Some(column) = columns
+		.iter()
+		.find(|column| column.is_empty() || !column.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+	{
+		return Err(Error::Serialization(format!(
+			"column name can't be used as a parameter name: {:?}",
+			column
+		)));
+	}
+	let sql = format!(
+		"INSERT INTO {} ({}) VALUES ({})",
+		quote_identifier(table),
+		columns
+			.iter()
+			.map(|column| quote_identifier(column))
+			.collect::<Vec<_>>()
+			.join(", "),
+		columns
+			.iter()
+			.map(|column| format!(":{}", column))
+			.collect::<Vec<_>>()
+			.join(", ")
+	);
+	let transaction = if transaction {
+		Some(connection.unchecked_transaction()?)
+	} else {
+		None
+	};
+	let mut stmt = connection.prepare(&sql)?;
+	let mut out = 0;
+	for row in rows {
+		let params = to_params_named_with_fields(row, columns)?;
+		// don't let the columns missing from this row keep the values bound for the previous one
+		stmt.clear_bindings();
+		out += stmt.execute(params.to_slice().as_slice())?;
+	}
+	drop(stmt);
+	if let Some(transaction) = transaction {
+		transaction.commit()?;
+	}
+	Ok(out)
+}
+
+/// Quotes SQL identifier with double quotes
+fn quote_identifier(name: &str) -> String {
+	format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Strips the prefix (`:`, `@` or `$`) from the name of the named bound query argument
+fn strip_param_prefix(name: &str) -> &str {
+	name.strip_prefix([':', '@', '$']).unwrap_or(name)
+}
+
+/// Checks that the fields of `T` match the columns of `table`
+///
+/// The field names are collected by serializing `T::default()` the same way `to_params_named()` does (so
+/// `#[serde(rename)]`, `#[serde(skip)]` and `#[serde(flatten)]` are taken into account) and compared with the result of
+/// `PRAGMA table_info`. Fails with `Error::SchemaMismatch` listing the fields without a column and the columns without a
+/// field. Use it before a batch of inserts or queries to catch the schema drift early.
+pub fn validate_struct_against_table<T: serde::Serialize + Default>(
+	connection: &rusqlite::Connection,
+	table: &str,
+) -> Result<()> {
+	let fields = to_params_named(T::default())?
+		.iter()
+		.map(|(name, _)| strip_param_prefix(name).to_owned())
+		.collect::<Vec<_>>();
+	let mut stmt = connection.prepare("SELECT name FROM pragma_table_info(?1)")?;
+	let columns = stmt
+		.query_map([table], |row| row.get::<_, String>(0))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	let missing = fields
+		.iter()
+		.filter(|field| !columns.contains(field))
+		.cloned()
+		.collect::<Vec<_>>();
+	let extra = columns
+		.iter()
+		.filter(|column| !fields.contains(column))
+		.cloned()
+		.collect::<Vec<_>>();
+	if missing.is_empty() && extra.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::SchemaMismatch {
+			table: table.to_owned(),
+			missing,
+			extra,
+		})
+	}
+}
@@ -0,0 +1,118 @@
+//! Helpers to store `std::net` IP addresses as `BLOB`s
+//!
+//! The addresses are stored as their octets in network byte order: 4 bytes for IPv4 and 16 bytes for IPv6. This is more
+//! compact than `TEXT` representation and the stored values sort in the same order as the addresses.
+//!
+//! Use them with `#[serde(with = "...")]` field attribute:
+//!
+//! ```
+//! use std::net::{IpAddr, Ipv4Addr};
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::net::ipv4_addr")]
+//!    gateway: Ipv4Addr,
+//!    #[serde(with = "serde_rusqlite::net::ip_addr")]
+//!    peer: IpAddr,
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Visitor};
+use serde::Deserializer;
+
+struct OctetsVisitor<const N: usize>(&'static str);
+
+impl<const N: usize> Visitor<'_> for OctetsVisitor<N> {
+	type Value = [u8; N];
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		write!(formatter, "BLOB of {} bytes for {}", N, self.0)
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+	}
+}
+
+fn deserialize_octets<'de, D: Deserializer<'de>, const N: usize>(
+	deserializer: D,
+	name: &'static str,
+) -> Result<[u8; N], D::Error> {
+	deserializer.deserialize_byte_buf(OctetsVisitor(name))
+}
+
+/// Stores `std::net::Ipv4Addr` as 4 byte `BLOB`
+pub mod ipv4_addr {
+	use std::net::Ipv4Addr;
+
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &Ipv4Addr, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&value.octets())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv4Addr, D::Error> {
+		super::deserialize_octets::<_, 4>(deserializer, "Ipv4Addr").map(Ipv4Addr::from)
+	}
+}
+
+/// Stores `std::net::Ipv6Addr` as 16 byte `BLOB`
+pub mod ipv6_addr {
+	use std::net::Ipv6Addr;
+
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &Ipv6Addr, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&value.octets())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ipv6Addr, D::Error> {
+		super::deserialize_octets::<_, 16>(deserializer, "Ipv6Addr").map(Ipv6Addr::from)
+	}
+}
+
+/// Stores `std::net::IpAddr` as 4 byte (IPv4) or 16 byte (IPv6) `BLOB`
+///
+/// The version of the address is determined by the length of the `BLOB` during deserialization. Note that IPv4-mapped IPv6
+/// addresses (e.g. `::ffff:1.2.3.4`) are kept as IPv6.
+pub mod ip_addr {
+	use std::fmt;
+	use std::net::IpAddr;
+
+	use serde::de::{self, Visitor};
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &IpAddr, serializer: S) -> Result<S::Ok, S::Error> {
+		match value {
+			IpAddr::V4(addr) => super::ipv4_addr::serialize(addr, serializer),
+			IpAddr::V6(addr) => super::ipv6_addr::serialize(addr, serializer),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpAddr, D::Error> {
+		deserializer.deserialize_byte_buf(IpAddrVisitor)
+	}
+
+	struct IpAddrVisitor;
+
+	impl Visitor<'_> for IpAddrVisitor {
+		type Value = IpAddr;
+
+		fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+			formatter.write_str("BLOB of 4 or 16 bytes for IpAddr")
+		}
+
+		fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+			if let Ok(octets) = <[u8; 4]>::try_from(v) {
+				Ok(IpAddr::from(octets))
+			} else if let Ok(octets) = <[u8; 16]>::try_from(v) {
+				Ok(IpAddr::from(octets))
+			} else {
+				Err(E::invalid_length(v.len(), &self))
+			}
+		}
+	}
+}
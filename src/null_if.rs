@@ -0,0 +1,59 @@
+//! Helpers to store `Option<i64>` using a sentinel `INTEGER` instead of `NULL` for `None`
+//!
+//! Useful for the legacy schemas that use a special value like `-1` to mark the missing value in a `NOT NULL` column.
+//! `None` is stored as the sentinel and both the sentinel and `NULL` are read back as `None`, all other values are stored
+//! and read as is. Note that `Some(sentinel)` can't round-trip and is read back as `None`.
+//!
+//! The sentinel is a const generic parameter so the functions need to be passed with `serialize_with` and
+//! `deserialize_with`, modules for the common sentinels can be used with `with` instead:
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::null_if::minus_one")]
+//!    parent_id: Option<i64>,
+//!    #[serde(
+//!       serialize_with = "serde_rusqlite::null_if::serialize::<999, _>",
+//!       deserialize_with = "serde_rusqlite::null_if::deserialize::<999, _>"
+//!    )]
+//!    code: Option<i64>,
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<const SENTINEL: i64, S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+	value.unwrap_or(SENTINEL).serialize(serializer)
+}
+
+pub fn deserialize<'de, const SENTINEL: i64, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+	Ok(Option::<i64>::deserialize(deserializer)?.filter(|value| *value != SENTINEL))
+}
+
+/// Uses `-1` as the sentinel for `None`
+pub mod minus_one {
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize::<-1, S>(value, serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+		super::deserialize::<-1, D>(deserializer)
+	}
+}
+
+/// Uses `0` as the sentinel for `None`
+pub mod zero {
+	use serde::{Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize::<0, S>(value, serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+		super::deserialize::<0, D>(deserializer)
+	}
+}
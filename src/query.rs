@@ -0,0 +1,99 @@
+use std::mem::ManuallyDrop;
+use std::ptr::NonNull;
+
+use rusqlite::{Connection, Statement};
+use serde::de::DeserializeOwned;
+
+use crate::{DeserRows, Result};
+
+/// Extension trait to prepare, bind, run and deserialize a query in one call
+///
+/// ```
+/// use serde_derive::Deserialize;
+/// use serde_rusqlite::QueryAs;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Example {
+///    id: i64,
+///    name: String,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection.execute_batch("CREATE TABLE example (id INT, name TEXT); INSERT INTO example VALUES (1, 'first')").unwrap();
+/// let mut rows = connection.query_as::<Example, _>("SELECT * FROM example WHERE id = ?", (1,)).unwrap();
+/// assert_eq!(rows.next().unwrap().unwrap(), Example { id: 1, name: "first".into() });
+/// ```
+pub trait QueryAs {
+	/// Prepares `sql`, binds serialized `params` and returns iterator deserializing the resulting rows into instances of
+	/// `D: serde::Deserialize`
+	///
+	/// If the statement uses named parameters (`:name`, `@name` or `$name`) then `params` are serialized with
+	/// `to_params_named()`, otherwise `to_params()` is used. The returned iterator owns the prepared statement so it only
+	/// borrows the connection.
+	fn query_as<D: DeserializeOwned, P: serde::Serialize>(&self, sql: &str, params: P) -> Result<DeserStatementRows<'_, D>>;
+}
+
+impl QueryAs for Connection {
+	fn query_as<D: DeserializeOwned, P: serde::Serialize>(&self, sql: &str, params: P) -> Result<DeserStatementRows<'_, D>> {
+		DeserStatementRows::new(self.prepare(sql)?, params)
+	}
+}
+
+/// Iterator that owns both the `rusqlite::Statement` and its `rusqlite::Rows` and deserializes each row into
+/// `D: serde::Deserialize`
+///
+/// You get it from `QueryAs::query_as()`.
+pub struct DeserStatementRows<'conn, D> {
+	// `rows` borrows the statement behind `stmt`, the statement is only freed after `rows` is dropped
+	rows: ManuallyDrop<DeserRows<'conn, D>>,
+	stmt: NonNull<Statement<'conn>>,
+}
+
+impl<'conn, D: DeserializeOwned> DeserStatementRows<'conn, D> {
+	fn new<P: serde::Serialize>(stmt: Statement<'conn>, params: P) -> Result<Self> {
+		let stmt = NonNull::from(Box::leak(Box::new(stmt)));
+		// SAFETY: the statement is heap allocated so its address is stable, it's only accessed through the `Rows` that are
+		// created here and it's freed only after those `Rows` are dropped
+		let rows = query_statement(unsafe { &mut *stmt.as_ptr() }, params);
+		match rows {
+			Ok(rows) => Ok(Self {
+				rows: ManuallyDrop::new(DeserRows::new(rows)),
+				stmt,
+			}),
+			Err(e) => {
+				// SAFETY: the pointer comes from `Box::leak()` above and there are no more references to the statement
+				drop(unsafe { Box::from_raw(stmt.as_ptr()) });
+				Err(e)
+			}
+		}
+	}
+}
+
+impl<D: DeserializeOwned> Iterator for DeserStatementRows<'_, D> {
+	type Item = Result<D>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.rows.next()
+	}
+}
+
+impl<D> Drop for DeserStatementRows<'_, D> {
+	fn drop(&mut self) {
+		// SAFETY: `rows` is never used after this point and the statement is freed only after `rows` that borrow it
+		unsafe {
+			ManuallyDrop::drop(&mut self.rows);
+			drop(Box::from_raw(self.stmt.as_ptr()));
+		}
+	}
+}
+
+fn query_statement<'stmt, P: serde::Serialize>(stmt: &'stmt mut Statement, params: P) -> Result<rusqlite::Rows<'stmt>> {
+	// numbered positional parameters like `?1` have names too, only the prefixed ones are named
+	let is_named = stmt.parameter_count() > 0 && stmt.parameter_name(1).is_some_and(|name| name.starts_with([':', '@', '$']));
+	if is_named {
+		let params = crate::to_params_named(params)?;
+		Ok(stmt.query(params.to_slice().as_slice())?)
+	} else {
+		Ok(stmt.query(crate::to_params(params)?)?)
+	}
+}
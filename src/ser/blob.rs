@@ -2,8 +2,33 @@ use serde::ser;
 
 use crate::{Error, Result};
 
+#[cfg(feature = "serde_json")]
+use super::json::{json_error, json_text, JsonValueSerializer};
+
 pub struct BlobSerializer {
 	pub buf: Vec<u8>,
+	/// Set when the sequence is stored as JSON `TEXT`, see `SerializeOptions::compound_as_json()`
+	#[cfg(feature = "serde_json")]
+	pub json: Option<<JsonValueSerializer as ser::Serializer>::SerializeSeq>,
+}
+
+impl BlobSerializer {
+	pub fn new(len: Option<usize>) -> Self {
+		Self {
+			buf: Vec::with_capacity(len.unwrap_or(0)),
+			#[cfg(feature = "serde_json")]
+			json: None,
+		}
+	}
+
+	/// Creates the serializer that stores the sequence as JSON array `TEXT`, even if it contains only `u8`s
+	#[cfg(feature = "serde_json")]
+	pub fn json(len: Option<usize>) -> Result<Self> {
+		Ok(Self {
+			buf: vec![],
+			json: Some(ser::Serializer::serialize_seq(JsonValueSerializer, len).map_err(json_error)?),
+		})
+	}
 }
 
 impl ser::SerializeSeq for BlobSerializer {
@@ -11,15 +36,37 @@ impl ser::SerializeSeq for BlobSerializer {
 	type Error = Error;
 
 	fn serialize_element<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
+		#[cfg(feature = "serde_json")]
+		if let Some(json) = &mut self.json {
+			return ser::SerializeSeq::serialize_element(json, value).map_err(json_error);
+		}
 		self.buf.push(value.serialize(U8Serializer)?);
 		Ok(())
 	}
 
 	fn end(self) -> Result<Self::Ok> {
+		#[cfg(feature = "serde_json")]
+		if let Some(json) = self.json {
+			return ser::SerializeSeq::end(json).map(json_text).map_err(json_error);
+		}
 		Ok(Box::new(self.buf))
 	}
 }
 
+/// Fixed-size arrays like `[u8; 32]` are serialized as tuples, they are stored the same way as sequences
+impl ser::SerializeTuple for BlobSerializer {
+	type Ok = Box<dyn rusqlite::types::ToSql>;
+	type Error = Error;
+
+	fn serialize_element<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
+		ser::SerializeSeq::serialize_element(self, value)
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		ser::SerializeSeq::end(self)
+	}
+}
+
 pub struct U8Serializer;
 
 impl ser::Serializer for U8Serializer {
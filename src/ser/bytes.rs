@@ -0,0 +1,32 @@
+use serde::{Serialize, Serializer};
+
+/// Wrapper that serializes the contained bytes as a single `BLOB`
+///
+/// serde passes `[u8; N]` to the serializer as a tuple of `u8`s, so a top-level byte array given to `to_params()` binds
+/// an `INTEGER` argument per byte. Wrap the array (or anything else that is `AsRef<[u8]>`) to bind it as one `BLOB`
+/// argument instead, both at the top level and as an element of a tuple:
+///
+/// ```
+/// use serde_rusqlite::ser::Blob;
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let hash = [0xde_u8, 0xad, 0xbe, 0xef];
+/// let blob: Vec<u8> = connection
+///    .query_row("SELECT ?1", serde_rusqlite::to_params(Blob(hash)).unwrap(), |row| row.get(0))
+///    .unwrap();
+/// assert_eq!(blob, hash);
+/// let (id, blob): (i64, Vec<u8>) = connection
+///    .query_row("SELECT ?1, ?2", serde_rusqlite::to_params((1, Blob(&hash))).unwrap(), |row| {
+///       Ok((row.get(0)?, row.get(1)?))
+///    })
+///    .unwrap();
+/// assert_eq!((id, blob), (1, hash.to_vec()));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Blob<T>(pub T);
+
+impl<T: AsRef<[u8]>> Serialize for Blob<T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(self.0.as_ref())
+	}
+}
@@ -0,0 +1,79 @@
+use rusqlite::types::ToSql;
+use serde::ser;
+
+pub use serde_json::value::Serializer as JsonValueSerializer;
+
+use crate::{Error, Result};
+
+pub fn json_text(value: serde_json::Value) -> Box<dyn ToSql> {
+	Box::new(value.to_string())
+}
+
+pub fn json_error(e: serde_json::Error) -> Error {
+	ser::Error::custom(e)
+}
+
+/// Adapter for the compound serializers of `JsonValueSerializer` producing JSON `TEXT` as the end result
+pub struct JsonCompound<C> {
+	pub inner: C,
+}
+
+impl<C: ser::SerializeMap<Ok = serde_json::Value, Error = serde_json::Error>> ser::SerializeMap for JsonCompound<C> {
+	type Ok = Box<dyn ToSql>;
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<()> {
+		self.inner.serialize_key(key).map_err(json_error)
+	}
+
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
+		self.inner.serialize_value(value).map_err(json_error)
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.inner.end().map(json_text).map_err(json_error)
+	}
+}
+
+impl<C: ser::SerializeStruct<Ok = serde_json::Value, Error = serde_json::Error>> ser::SerializeStruct for JsonCompound<C> {
+	type Ok = Box<dyn ToSql>;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+		self.inner.serialize_field(key, value).map_err(json_error)
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.inner.end().map(json_text).map_err(json_error)
+	}
+}
+
+impl<C: ser::SerializeTupleVariant<Ok = serde_json::Value, Error = serde_json::Error>> ser::SerializeTupleVariant
+	for JsonCompound<C>
+{
+	type Ok = Box<dyn ToSql>;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
+		self.inner.serialize_field(value).map_err(json_error)
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.inner.end().map(json_text).map_err(json_error)
+	}
+}
+
+impl<C: ser::SerializeStructVariant<Ok = serde_json::Value, Error = serde_json::Error>> ser::SerializeStructVariant
+	for JsonCompound<C>
+{
+	type Ok = Box<dyn ToSql>;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+		self.inner.serialize_field(key, value).map_err(json_error)
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.inner.end().map(json_text).map_err(json_error)
+	}
+}
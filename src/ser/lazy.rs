@@ -0,0 +1,64 @@
+use rusqlite::types::ToSqlOutput;
+use rusqlite::ToSql;
+
+use crate::{Error, PositionalSliceSerializer, SerializeOptions};
+
+/// Iterator that serializes positional bound query arguments one by one
+///
+/// You shouldn't use it directly, but via the crate's `to_params_lazy()` function.
+///
+/// Serde serialization can't be suspended, so to produce the next argument the whole value is walked again, but only the
+/// element at the current position is actually converted. Only one serialized argument is kept in memory at any time
+/// which pays off for values with a lot of large elements (e.g. `BLOB`s), but costs quadratic number of steps in the
+/// amount of elements. For the common case of a few small arguments `to_params()` is faster.
+pub struct LazyParams<S> {
+	obj: S,
+	options: SerializeOptions,
+	idx: usize,
+	finished: bool,
+}
+
+impl<S: serde::Serialize> LazyParams<S> {
+	pub fn new(obj: S, options: SerializeOptions) -> Self {
+		Self {
+			obj,
+			options,
+			idx: 0,
+			finished: false,
+		}
+	}
+}
+
+impl<S: serde::Serialize> Iterator for LazyParams<S> {
+	type Item = Box<dyn ToSql>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.finished {
+			return None;
+		}
+		self.idx += 1;
+		match self
+			.obj
+			.serialize(PositionalSliceSerializer::with_only_element(self.options, self.idx - 1))
+		{
+			Ok(mut params) => {
+				let out = params.pop();
+				self.finished = out.is_none();
+				out
+			}
+			Err(e) => {
+				self.finished = true;
+				Some(Box::new(FailedParam(e)))
+			}
+		}
+	}
+}
+
+/// Argument that reports the serialization error when rusqlite tries to bind it
+pub(super) struct FailedParam(pub(super) Error);
+
+impl ToSql for FailedParam {
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+		Err(rusqlite::Error::ToSqlConversionFailure(self.0.to_string().into()))
+	}
+}
@@ -1,7 +1,14 @@
 pub use super::{Error, Result};
 
+pub use self::bytes::Blob;
+pub use self::lazy::LazyParams;
 pub use self::named::NamedSliceSerializer;
+pub use self::names::FieldNamesSerializer;
+pub use self::options::SerializeOptions;
 pub use self::positional::{PositionalParams, PositionalSliceSerializer};
+pub(crate) use self::raw::{decode as decode_raw_value, encode as encode_raw_value};
+pub use self::raw::{Raw, RAW_VALUE_NEWTYPE};
+pub use self::serialized::Serialized;
 pub use self::slice::NamedParamSlice;
 
 macro_rules! ser_unimpl {
@@ -13,7 +20,24 @@ macro_rules! ser_unimpl {
 }
 
 mod blob;
+mod bytes;
+#[cfg(feature = "serde_json")]
+mod json;
+mod lazy;
 mod named;
+mod names;
+mod options;
 mod positional;
+mod raw;
+mod serialized;
 mod slice;
 mod tosql;
+
+/// Extracts the owned `Value` from the serialized query argument
+pub(crate) fn param_to_value(param: &dyn rusqlite::ToSql) -> Result<rusqlite::types::Value> {
+	match param.to_sql()? {
+		rusqlite::types::ToSqlOutput::Borrowed(value) => Ok(value.into()),
+		rusqlite::types::ToSqlOutput::Owned(value) => Ok(value),
+		_ => Err(Error::ser_unsupported("special ToSqlOutput")),
+	}
+}
@@ -1,6 +1,7 @@
+use rusqlite::types::{ToSql, ToSqlOutput, Value, ValueRef};
 use serde::ser;
 
-use crate::{Error, NamedParamSlice, Result};
+use crate::{Error, NamedParamSlice, Result, SerializeOptions};
 
 use super::tosql::ToSqlSerializer;
 
@@ -12,21 +13,52 @@ pub struct NamedSliceSerializer<'f> {
 	pub result: NamedParamSlice,
 	entry_key: Option<String>,
 	only_fields: &'f [&'f str],
+	options: SerializeOptions,
 }
 
 impl<'f> NamedSliceSerializer<'f> {
 	pub fn with_only_fields(only_fields: &'f [&'f str]) -> Self {
+		Self::with_only_fields_and_options(only_fields, SerializeOptions::default())
+	}
+
+	pub fn with_only_fields_and_options(only_fields: &'f [&'f str], options: SerializeOptions) -> Self {
 		Self {
 			result: NamedParamSlice::default(),
 			entry_key: None,
 			only_fields,
+			options,
+		}
+	}
+
+	pub fn with_options(options: SerializeOptions) -> Self {
+		Self {
+			options,
+			..Self::default()
 		}
 	}
 
+	/// Creates the serializer producing the names that start with `prefix` instead of `:`
+	///
+	/// Fails with `Error::Serialization` if `prefix` is not one of `:`, `@` or `$`.
+	pub fn with_prefix(prefix: char) -> Result<Self> {
+		let options = SerializeOptions::default().named_prefix(prefix);
+		named_prefix(&options)?;
+		Ok(Self::with_options(options))
+	}
+
+	/// Shortcut for `with_options()` with `SerializeOptions::enum_as_index()` set
+	pub fn with_enum_as_index() -> Self {
+		Self::with_options(SerializeOptions::default().enum_as_index(true))
+	}
+
 	#[inline]
 	fn add_entry(&mut self, key: &str, value: impl serde::Serialize) -> Result<()> {
 		if self.only_fields.is_empty() || self.only_fields.contains(&key) {
-			self.result.push((format!(":{}", key), value.serialize(ToSqlSerializer)?));
+			let value = value.serialize(ToSqlSerializer { options: self.options })?;
+			if self.options.skip_none && is_null(value.as_ref())? {
+				return Ok(());
+			}
+			self.result.push((format!("{}{}", named_prefix(&self.options)?, key), value));
 		}
 		Ok(())
 	}
@@ -93,13 +125,13 @@ impl ser::Serializer for NamedSliceSerializer<'_> {
 	ser_unimpl!(serialize_bytes, &[u8]);
 
 	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-		Err(Error::ser_unsupported("seq"))
+		Err(positional_unsupported("seq"))
 	}
 	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-		Err(Error::ser_unsupported("tuple"))
+		Err(positional_unsupported("tuple"))
 	}
 	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
-		Err(Error::ser_unsupported("tuple_struct"))
+		Err(positional_unsupported("tuple_struct"))
 	}
 	fn serialize_tuple_variant(
 		self,
@@ -108,7 +140,7 @@ impl ser::Serializer for NamedSliceSerializer<'_> {
 		_variant: &'static str,
 		_len: usize,
 	) -> Result<Self::SerializeTupleVariant> {
-		Err(Error::ser_unsupported("tuple_variant"))
+		Err(positional_unsupported("tuple_variant"))
 	}
 	fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap> {
 		if let Some(len) = len {
@@ -179,7 +211,35 @@ impl ser::SerializeStructVariant for NamedSliceSerializer<'_> {
 	}
 }
 
-struct ColumNameSerializer;
+/// Error for the values that can only be serialized as positional arguments
+fn positional_unsupported(typ: &str) -> Error {
+	Error::Unsupported(format!(
+		"Serialization is not supported from type: {}, named params require a struct or map; use to_params for tuples/sequences",
+		typ
+	))
+}
+
+fn named_prefix(options: &SerializeOptions) -> Result<char> {
+	match options.named_prefix {
+		None => Ok(':'),
+		Some(prefix @ (':' | '@' | '$')) => Ok(prefix),
+		Some(prefix) => Err(Error::Serialization(format!(
+			"invalid named parameter prefix: {}, expected one of: ':', '@', '$'",
+			prefix
+		))),
+	}
+}
+
+/// Checks whether `value` is bound as SQL `NULL`, that includes `NaN` floats
+fn is_null(value: &dyn ToSql) -> Result<bool> {
+	Ok(match value.to_sql()? {
+		ToSqlOutput::Borrowed(ValueRef::Null) | ToSqlOutput::Owned(Value::Null) => true,
+		ToSqlOutput::Borrowed(ValueRef::Real(val)) | ToSqlOutput::Owned(Value::Real(val)) => val.is_nan(),
+		_ => false,
+	})
+}
+
+pub(super) struct ColumNameSerializer;
 
 impl ser::Serializer for ColumNameSerializer {
 	type Ok = String;
@@ -0,0 +1,159 @@
+use serde::ser;
+
+use crate::{Error, Result};
+
+use super::named::ColumNameSerializer;
+
+/// Serializer collecting the field names of a `struct` or the keys of a map, the values are not serialized at all
+///
+/// You shouldn't use it directly, but via the crate's `field_names()` function.
+#[derive(Default)]
+pub struct FieldNamesSerializer {
+	names: Vec<String>,
+}
+
+impl ser::Serializer for FieldNamesSerializer {
+	type Ok = Vec<String>;
+	type Error = Error;
+	type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeMap = Self;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Self;
+
+	fn serialize_none(self) -> Result<Self::Ok> {
+		Err(Error::ser_unsupported("None"))
+	}
+
+	fn serialize_some<T: ?Sized + serde::Serialize>(self, value: &T) -> Result<Self::Ok> {
+		value.serialize(self)
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok> {
+		Err(Error::ser_unsupported("()"))
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+		Err(Error::ser_unsupported("unit_struct"))
+	}
+
+	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str) -> Result<Self::Ok> {
+		Err(Error::ser_unsupported("unit_variant"))
+	}
+
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok> {
+		value.serialize(self)
+	}
+
+	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok> {
+		value.serialize(self)
+	}
+
+	ser_unimpl!(serialize_bool, bool);
+	ser_unimpl!(serialize_i8, i8);
+	ser_unimpl!(serialize_i16, i16);
+	ser_unimpl!(serialize_i32, i32);
+	ser_unimpl!(serialize_i64, i64);
+	ser_unimpl!(serialize_u8, u8);
+	ser_unimpl!(serialize_u16, u16);
+	ser_unimpl!(serialize_u32, u32);
+	ser_unimpl!(serialize_u64, u64);
+	ser_unimpl!(serialize_f32, f32);
+	ser_unimpl!(serialize_f64, f64);
+	ser_unimpl!(serialize_str, &str);
+	ser_unimpl!(serialize_char, char);
+	ser_unimpl!(serialize_bytes, &[u8]);
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+		Err(Error::ser_unsupported("seq"))
+	}
+	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+		Err(Error::ser_unsupported("tuple"))
+	}
+	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
+		Err(Error::ser_unsupported("tuple_struct"))
+	}
+	fn serialize_tuple_variant(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_len: usize,
+	) -> Result<Self::SerializeTupleVariant> {
+		Err(Error::ser_unsupported("tuple_variant"))
+	}
+	fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap> {
+		if let Some(len) = len {
+			self.names.reserve_exact(len);
+		}
+		Ok(self)
+	}
+	fn serialize_struct(mut self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+		self.names.reserve_exact(len);
+		Ok(self)
+	}
+	fn serialize_struct_variant(
+		mut self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant> {
+		self.names.reserve_exact(len);
+		Ok(self)
+	}
+}
+
+impl ser::SerializeMap for FieldNamesSerializer {
+	type Ok = Vec<String>;
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, key: &T) -> Result<()> {
+		self.names.push(key.serialize(ColumNameSerializer)?);
+		Ok(())
+	}
+
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, _value: &T) -> Result<()> {
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		Ok(self.names)
+	}
+}
+
+impl ser::SerializeStruct for FieldNamesSerializer {
+	type Ok = Vec<String>;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, key: &'static str, _value: &T) -> Result<()> {
+		self.names.push(key.to_owned());
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		Ok(self.names)
+	}
+}
+
+impl ser::SerializeStructVariant for FieldNamesSerializer {
+	type Ok = Vec<String>;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, key: &'static str, _value: &T) -> Result<()> {
+		self.names.push(key.to_owned());
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		Ok(self.names)
+	}
+}
@@ -0,0 +1,96 @@
+/// Options that change the way values are serialized into query arguments
+///
+/// The default options produce the same result as the crate's `to_params()` and `to_params_named()` functions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SerializeOptions {
+	pub(crate) float_as_text: bool,
+	pub(crate) error_on_empty: bool,
+	pub(crate) skip_none: bool,
+	pub(crate) enum_as_index: bool,
+	pub(crate) u64_as_text: bool,
+	pub(crate) named_prefix: Option<char>,
+	pub(crate) variant_tag: bool,
+	#[cfg(feature = "serde_json")]
+	pub(crate) compound_as_json: bool,
+}
+
+impl SerializeOptions {
+	/// Serialize `f32` and `f64` as `TEXT` instead of `REAL`
+	///
+	/// The shortest representation that parses back into exactly the same value is used, this allows exact round-tripping
+	/// when the float is deserialized with `DeserializeOptions::float_from_text()`. `NaN`s are still serialized as `NULL`s.
+	pub fn float_as_text(mut self, float_as_text: bool) -> Self {
+		self.float_as_text = float_as_text;
+		self
+	}
+
+	/// Fail positional serialization with `Error::Serialization` if it produces no arguments
+	///
+	/// Empty sequences and tuples (e.g. `Vec::new()` or `[0; 0]`) produce no positional arguments at all, unlike `()` and
+	/// `None` that produce a single `NULL` argument. Use this option to catch the cases when such an empty value is passed
+	/// to the statement that expects arguments.
+	pub fn error_on_empty(mut self, error_on_empty: bool) -> Self {
+		self.error_on_empty = error_on_empty;
+		self
+	}
+
+	/// Don't produce named arguments for the fields and map entries that serialize to `NULL`
+	///
+	/// Useful for partial updates that should only touch the fields that are present. Note that any value that becomes
+	/// `NULL` is skipped: besides `None` that's also `()` and `NaN` floats.
+	pub fn skip_none(mut self, skip_none: bool) -> Self {
+		self.skip_none = skip_none;
+		self
+	}
+
+	/// Serialize unit variants of `enum`s as `INTEGER` index of the variant instead of its name as `TEXT`
+	///
+	/// The index is the position of the variant in the declaration order starting at 0, explicit discriminants are not
+	/// taken into account, use the `discriminant` module for those. Deserialization accepts both representations.
+	pub fn enum_as_index(mut self, enum_as_index: bool) -> Self {
+		self.enum_as_index = enum_as_index;
+		self
+	}
+
+	/// Serialize `u64` values that don't fit into `i64` as decimal `TEXT` instead of failing with `Error::ValueTooLarge`
+	///
+	/// The values up to `i64::MAX` are still stored as `INTEGER`s. `u64` is deserialized from such `TEXT` without any
+	/// additional options, other `TEXT` is only read into `u64` with `DeserializeOptions::numeric_from_text()`.
+	pub fn u64_as_text(mut self, u64_as_text: bool) -> Self {
+		self.u64_as_text = u64_as_text;
+		self
+	}
+
+	/// Use `prefix` instead of `:` for the names of the named arguments
+	///
+	/// SQLite also accepts `@name` and `$name` parameters. Any other `prefix` makes named serialization fail with
+	/// `Error::Serialization`.
+	pub fn named_prefix(mut self, prefix: char) -> Self {
+		self.named_prefix = Some(prefix);
+		self
+	}
+
+	/// Prepend the variant name as `TEXT` to the positional arguments of the newtype, tuple and struct `enum` variants
+	///
+	/// E.g. `Event::Login(5)` produces `"Login", 5` arguments to match `(type, value)` columns instead of just `5`. The
+	/// variant index is used as `INTEGER` instead of the name if `enum_as_index()` is also set. Named serialization is
+	/// not affected.
+	pub fn variant_tag(mut self, variant_tag: bool) -> Self {
+		self.variant_tag = variant_tag;
+		self
+	}
+
+	/// Store the values that don't map to a single SQLite value as JSON `TEXT` instead of failing
+	///
+	/// This covers maps, `struct`s, `enum` variants with data and sequences and tuples. The storage class depends only on
+	/// the type: all sequences and tuples, including the ones of `u8` and empty ones, become JSON arrays, so use
+	/// `serde_bytes` for the fields that should stay `BLOB`s. Newtype `enum` variants are stored as JSON objects like
+	/// `{"Variant":value}` instead of just their value. The fields that ask for a map, `struct`, sequence or `enum` parse
+	/// such `TEXT` back during deserialization, for `serde_json::Value` fields also enable
+	/// `DeserializeOptions::parse_json_text()`.
+	#[cfg(feature = "serde_json")]
+	pub fn compound_as_json(mut self, compound_as_json: bool) -> Self {
+		self.compound_as_json = compound_as_json;
+		self
+	}
+}
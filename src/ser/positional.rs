@@ -1,20 +1,21 @@
 use rusqlite::ToSql;
 use serde::ser;
 
-use crate::{Error, Result};
+use crate::{Error, Result, SerializeOptions};
 
+use super::raw::RAW_VALUE_NEWTYPE;
 use super::tosql::ToSqlSerializer;
 
 macro_rules! forward_tosql {
 	($fun:ident, $type:ty) => {
 		fn $fun(mut self, v: $type) -> Result<Self::Ok> {
-			self.result.push(ToSqlSerializer.$fun(v)?);
+			self.push(|ser| ser.$fun(v))?;
 			Ok(self.result)
 		}
 	};
 	($fun:ident) => {
 		fn $fun(mut self) -> Result<Self::Ok> {
-			self.result.push(ToSqlSerializer.$fun()?);
+			self.push(|ser| ser.$fun())?;
 			Ok(self.result)
 		}
 	};
@@ -28,6 +29,72 @@ pub type PositionalParams = Vec<Box<dyn ToSql>>;
 #[derive(Default)]
 pub struct PositionalSliceSerializer {
 	pub result: PositionalParams,
+	options: SerializeOptions,
+	/// When set, only the element with this index is serialized into `result`
+	only_element: Option<usize>,
+	element_idx: usize,
+}
+
+impl PositionalSliceSerializer {
+	pub fn with_options(options: SerializeOptions) -> Self {
+		Self {
+			options,
+			..Self::default()
+		}
+	}
+
+	/// Shortcut for `with_options()` with `SerializeOptions::enum_as_index()` set
+	pub fn with_enum_as_index() -> Self {
+		Self::with_options(SerializeOptions::default().enum_as_index(true))
+	}
+
+	pub(crate) fn with_only_element(options: SerializeOptions, only_element: usize) -> Self {
+		Self {
+			options,
+			only_element: Some(only_element),
+			..Self::default()
+		}
+	}
+
+	#[inline]
+	fn tosql(&self) -> ToSqlSerializer {
+		ToSqlSerializer { options: self.options }
+	}
+
+	#[inline]
+	fn push(&mut self, serialize: impl FnOnce(ToSqlSerializer) -> Result<Box<dyn ToSql>>) -> Result<()> {
+		if self.only_element.is_none_or(|only_element| only_element == self.element_idx) {
+			let value = serialize(self.tosql())?;
+			self.result.push(value);
+		}
+		self.element_idx += 1;
+		Ok(())
+	}
+
+	/// Returns the collected arguments checking that there is at least one if `SerializeOptions::error_on_empty()` is set
+	fn finish(self) -> Result<PositionalParams> {
+		if self.options.error_on_empty && self.only_element.is_none() && self.result.is_empty() {
+			return Err(Error::Serialization(
+				"positional serialization produced no arguments".to_string(),
+			));
+		}
+		Ok(self.result)
+	}
+
+	/// Pushes the variant tag first if `SerializeOptions::variant_tag()` is set
+	fn push_variant_tag(&mut self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<()> {
+		if self.options.variant_tag {
+			self.push(|ser| ser::Serializer::serialize_unit_variant(ser, name, variant_index, variant))?;
+		}
+		Ok(())
+	}
+
+	#[inline]
+	fn reserve(&mut self, len: usize) {
+		if self.only_element.is_none() {
+			self.result.reserve_exact(len);
+		}
+	}
 }
 
 impl ser::Serializer for PositionalSliceSerializer {
@@ -37,9 +104,9 @@ impl ser::Serializer for PositionalSliceSerializer {
 	type SerializeTuple = Self;
 	type SerializeTupleStruct = Self;
 	type SerializeTupleVariant = Self;
-	type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
-	type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
-	type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeMap = Self;
+	type SerializeStruct = Self;
+	type SerializeStructVariant = Self;
 
 	forward_tosql!(serialize_bool, bool);
 	forward_tosql!(serialize_i8, i8);
@@ -50,6 +117,8 @@ impl ser::Serializer for PositionalSliceSerializer {
 	forward_tosql!(serialize_u16, u16);
 	forward_tosql!(serialize_u32, u32);
 	forward_tosql!(serialize_u64, u64);
+	forward_tosql!(serialize_i128, i128);
+	forward_tosql!(serialize_u128, u128);
 	forward_tosql!(serialize_f32, f32);
 	forward_tosql!(serialize_f64, f64);
 	forward_tosql!(serialize_str, &str);
@@ -63,73 +132,87 @@ impl ser::Serializer for PositionalSliceSerializer {
 	}
 
 	fn serialize_unit_struct(mut self, name: &'static str) -> Result<Self::Ok> {
-		self.result.push(ToSqlSerializer.serialize_unit_struct(name)?);
+		self.push(|ser| ser.serialize_unit_struct(name))?;
 		Ok(self.result)
 	}
 
 	fn serialize_unit_variant(mut self, name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok> {
-		self
-			.result
-			.push(ToSqlSerializer.serialize_unit_variant(name, variant_index, variant)?);
+		self.push(|ser| ser::Serializer::serialize_unit_variant(ser, name, variant_index, variant))?;
 		Ok(self.result)
 	}
 
-	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok> {
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(mut self, name: &'static str, value: &T) -> Result<Self::Ok> {
+		if name == RAW_VALUE_NEWTYPE {
+			self.push(|ser| ser.serialize_newtype_struct(name, value))?;
+			return Ok(self.result);
+		}
 		value.serialize(self)
 	}
 
 	fn serialize_newtype_variant<T: ?Sized + serde::Serialize>(
-		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
+		mut self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
 		value: &T,
 	) -> Result<Self::Ok> {
+		self.push_variant_tag(name, variant_index, variant)?;
 		value.serialize(self)
 	}
 
 	fn serialize_seq(mut self, len: Option<usize>) -> Result<Self::SerializeSeq> {
 		if let Some(len) = len {
-			self.result.reserve_exact(len);
+			self.reserve(len);
 		}
 		Ok(self)
 	}
 
 	fn serialize_tuple(mut self, len: usize) -> Result<Self::SerializeTuple> {
-		self.result.reserve_exact(len);
+		self.reserve(len);
 		Ok(self)
 	}
 
 	fn serialize_tuple_struct(mut self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct> {
-		self.result.reserve_exact(len);
+		self.reserve(len);
 		Ok(self)
 	}
 
 	fn serialize_tuple_variant(
 		mut self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
 		len: usize,
 	) -> Result<Self::SerializeTupleVariant> {
-		self.result.reserve_exact(len);
+		self.reserve(len + usize::from(self.options.variant_tag));
+		self.push_variant_tag(name, variant_index, variant)?;
 		Ok(self)
 	}
 
-	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-		Err(Error::ser_unsupported("map"))
+	/// Values are serialized in the iteration order of the map, the keys are ignored
+	fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap> {
+		if let Some(len) = len {
+			self.reserve(len);
+		}
+		Ok(self)
 	}
-	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-		Err(Error::ser_unsupported("struct"))
+
+	/// Field values are serialized in the declaration order, the names are ignored
+	fn serialize_struct(mut self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+		self.reserve(len);
+		Ok(self)
 	}
+
 	fn serialize_struct_variant(
-		self,
-		_name: &'static str,
-		_variant_index: u32,
-		_variant: &'static str,
-		_len: usize,
+		mut self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
 	) -> Result<Self::SerializeStructVariant> {
-		Err(Error::ser_unsupported("struct_variant"))
+		self.reserve(len + usize::from(self.options.variant_tag));
+		self.push_variant_tag(name, variant_index, variant)?;
+		Ok(self)
 	}
 }
 
@@ -138,12 +221,11 @@ impl ser::SerializeSeq for PositionalSliceSerializer {
 	type Error = Error;
 
 	fn serialize_element<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
-		self.result.push(value.serialize(ToSqlSerializer)?);
-		Ok(())
+		self.push(|ser| value.serialize(ser))
 	}
 
 	fn end(self) -> Result<Self::Ok> {
-		Ok(self.result)
+		self.finish()
 	}
 }
 
@@ -152,12 +234,11 @@ impl ser::SerializeTuple for PositionalSliceSerializer {
 	type Error = Error;
 
 	fn serialize_element<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
-		self.result.push(value.serialize(ToSqlSerializer)?);
-		Ok(())
+		self.push(|ser| value.serialize(ser))
 	}
 
 	fn end(self) -> Result<Self::Ok> {
-		Ok(self.result)
+		self.finish()
 	}
 }
 
@@ -166,12 +247,11 @@ impl ser::SerializeTupleStruct for PositionalSliceSerializer {
 	type Error = Error;
 
 	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
-		self.result.push(value.serialize(ToSqlSerializer)?);
-		Ok(())
+		self.push(|ser| value.serialize(ser))
 	}
 
 	fn end(self) -> Result<Self::Ok> {
-		Ok(self.result)
+		self.finish()
 	}
 }
 
@@ -180,11 +260,53 @@ impl ser::SerializeTupleVariant for PositionalSliceSerializer {
 	type Error = Error;
 
 	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
-		self.result.push(value.serialize(ToSqlSerializer)?);
+		self.push(|ser| value.serialize(ser))
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeMap for PositionalSliceSerializer {
+	type Ok = PositionalParams;
+	type Error = Error;
+
+	fn serialize_key<T: ?Sized + serde::Serialize>(&mut self, _key: &T) -> Result<()> {
 		Ok(())
 	}
 
+	fn serialize_value<T: ?Sized + serde::Serialize>(&mut self, value: &T) -> Result<()> {
+		self.push(|ser| value.serialize(ser))
+	}
+
 	fn end(self) -> Result<Self::Ok> {
-		Ok(self.result)
+		self.finish()
+	}
+}
+
+impl ser::SerializeStruct for PositionalSliceSerializer {
+	type Ok = PositionalParams;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, _key: &'static str, value: &T) -> Result<()> {
+		self.push(|ser| value.serialize(ser))
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.finish()
+	}
+}
+
+impl ser::SerializeStructVariant for PositionalSliceSerializer {
+	type Ok = PositionalParams;
+	type Error = Error;
+
+	fn serialize_field<T: ?Sized + serde::Serialize>(&mut self, _key: &'static str, value: &T) -> Result<()> {
+		self.push(|ser| value.serialize(ser))
+	}
+
+	fn end(self) -> Result<Self::Ok> {
+		self.finish()
 	}
 }
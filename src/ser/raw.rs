@@ -0,0 +1,165 @@
+use std::fmt;
+
+use rusqlite::types::Value;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+use crate::{Error, Result};
+
+/// Name of the newtype struct that passes the native SQLite value through the serialization unchanged
+///
+/// The newtype must contain bytes (`serialize_bytes()`) of the value encoded as a single tag byte followed by the payload:
+///
+/// * `0` - `NULL`, no payload
+/// * `1` - `INTEGER`, 8 bytes of little-endian `i64`
+/// * `2` - `REAL`, 8 bytes of little-endian `f64`
+/// * `3` - `TEXT`, UTF-8 bytes of the string
+/// * `4` - `BLOB`, the bytes themselves
+///
+/// During deserialization the newtype receives the value of the column encoded the same way via `visit_byte_buf()`.
+///
+/// This is the extension point for the libraries that need to inject the values that can't be expressed through serde
+/// data model, most users should use `Raw` wrapper that takes care of the encoding instead.
+pub const RAW_VALUE_NEWTYPE: &str = "__serde_rusqlite_raw";
+
+/// Wrapper that serializes the contained `rusqlite::types::Value` as is, bypassing the usual type mapping
+///
+/// ```
+/// use rusqlite::types::Value;
+/// use serde_rusqlite::ser::Raw;
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let params = serde_rusqlite::to_params((1, Raw(Value::Blob(vec![1, 2])))).unwrap();
+/// let blob: Vec<u8> = connection.query_row("SELECT ?2", params, |row| row.get(0)).unwrap();
+/// assert_eq!(blob, [1, 2]);
+/// ```
+///
+/// It also deserializes into the value exactly as stored in the column, e.g. `BLOB` stays `Value::Blob`, so rows of
+/// unknown shape can be read into `HashMap<String, Raw>` or, with `from_row_by_index()`, into `BTreeMap<usize, Raw>`:
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use rusqlite::types::Value;
+/// use serde_rusqlite::ser::Raw;
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// let row = connection
+///    .query_row("SELECT 1, x'0102', NULL", [], |row| {
+///       Ok(serde_rusqlite::from_row_by_index::<BTreeMap<usize, Raw>>(row).unwrap())
+///    })
+///    .unwrap();
+/// let values = row.into_values().map(|Raw(value)| value).collect::<Vec<_>>();
+/// assert_eq!(values, [Value::Integer(1), Value::Blob(vec![1, 2]), Value::Null]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raw(pub Value);
+
+impl serde::Serialize for Raw {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.serialize_newtype_struct(RAW_VALUE_NEWTYPE, &EncodedValue(encode(&self.0)))
+	}
+}
+
+impl<'de> serde::Deserialize<'de> for Raw {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		deserializer.deserialize_newtype_struct(RAW_VALUE_NEWTYPE, RawVisitor)
+	}
+}
+
+/// Receives the value encoded for `RAW_VALUE_NEWTYPE` from this crate's deserializers
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+	type Value = Raw;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("encoded SQLite value")
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+		decode(v).map(Raw).map_err(E::custom)
+	}
+
+	/// Other deserializers don't know about `RAW_VALUE_NEWTYPE` and pass their value as is
+	fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(ValueVisitor)
+	}
+}
+
+/// Maps the serde data model value to the closest SQLite value
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+	type Value = Raw;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("SQLite value")
+	}
+
+	fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Integer(i64::from(v))))
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Integer(v)))
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Self::Value, E> {
+		i64::try_from(v)
+			.map(|v| Raw(Value::Integer(v)))
+			.map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+	}
+
+	fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Real(v)))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Text(v.to_owned())))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Blob(v.to_vec())))
+	}
+
+	fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Null))
+	}
+
+	fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+		Ok(Raw(Value::Null))
+	}
+}
+
+struct EncodedValue(Vec<u8>);
+
+impl serde::Serialize for EncodedValue {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		serializer.serialize_bytes(&self.0)
+	}
+}
+
+/// Encodes `value` for `RAW_VALUE_NEWTYPE`
+pub fn encode(value: &Value) -> Vec<u8> {
+	match value {
+		Value::Null => vec![0],
+		Value::Integer(val) => [&[1][..], &val.to_le_bytes()].concat(),
+		Value::Real(val) => [&[2][..], &val.to_le_bytes()].concat(),
+		Value::Text(val) => [&[3][..], val.as_bytes()].concat(),
+		Value::Blob(val) => [&[4][..], val].concat(),
+	}
+}
+
+/// Decodes the payload of `RAW_VALUE_NEWTYPE`
+pub fn decode(bytes: &[u8]) -> Result<Value> {
+	let invalid = || Error::Serialization(format!("invalid encoding of the {} value", RAW_VALUE_NEWTYPE));
+	let (tag, payload) = bytes.split_first().ok_or_else(invalid)?;
+	match tag {
+		0 if payload.is_empty() => Ok(Value::Null),
+		1 => Ok(Value::Integer(i64::from_le_bytes(payload.try_into().map_err(|_| invalid())?))),
+		2 => Ok(Value::Real(f64::from_le_bytes(payload.try_into().map_err(|_| invalid())?))),
+		3 => Ok(Value::Text(String::from_utf8(payload.to_vec()).map_err(|_| invalid())?)),
+		4 => Ok(Value::Blob(payload.to_vec())),
+		_ => Err(invalid()),
+	}
+}
@@ -0,0 +1,47 @@
+use rusqlite::types::{ToSqlOutput, Value};
+use rusqlite::ToSql;
+
+use crate::Error;
+
+use super::tosql::ToSqlSerializer;
+
+/// Wrapper that binds any single value `T: serde::Serialize` as a query argument
+///
+/// Allows mixing the serde-backed values with the regular ones in `params![]` without going through `to_params()`:
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_rusqlite::Serialized;
+///
+/// #[derive(Serialize)]
+/// enum Status {
+///    Active,
+/// }
+///
+/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+/// connection.execute("CREATE TABLE example (status TEXT, id INT)", []).unwrap();
+/// connection.execute("INSERT INTO example VALUES (?, ?)", rusqlite::params![Serialized(Status::Active), 42]).unwrap();
+/// ```
+///
+/// `T` is serialized the same way as a field of a `struct` with the default `SerializeOptions`. When it doesn't map to a
+/// single SQLite value (e.g. a `struct` or a `tuple`) binding fails with `rusqlite::Error::ToSqlConversionFailure`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Serialized<T>(pub T);
+
+impl<T: serde::Serialize> ToSql for Serialized<T> {
+	fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+		let value = self
+			.0
+			.serialize(ToSqlSerializer::default())
+			.map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+		Ok(match value.to_sql()? {
+			ToSqlOutput::Borrowed(value) => ToSqlOutput::Owned(Value::from(value)),
+			ToSqlOutput::Owned(value) => ToSqlOutput::Owned(value),
+			_ => {
+				return Err(rusqlite::Error::ToSqlConversionFailure(Box::new(Error::ser_unsupported(
+					"special ToSqlOutput",
+				))))
+			}
+		})
+	}
+}
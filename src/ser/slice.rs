@@ -1,10 +1,19 @@
 use std::borrow::Borrow;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
 
+use rusqlite::types::{ToSql, ToSqlOutput};
+
+use super::lazy::FailedParam;
+use crate::Error;
+
 /// Stores named bound query arguments
 ///
 /// This `struct` stores data for passing as argument slice to `*_named()` query functions of rusqlite.
 /// To get the instance call crate's `to_named_params()` function.
+///
+/// Cloning converts the arguments to owned values, so the clone can be bound to other statements independently. `Debug`
+/// output shows the names and the values that are going to be bound.
 #[derive(Default)]
 pub struct NamedParamSlice(Vec<(String, Box<dyn rusqlite::types::ToSql>)>);
 
@@ -12,6 +21,69 @@ impl NamedParamSlice {
 	pub fn to_slice(&self) -> Vec<(&str, &dyn rusqlite::types::ToSql)> {
 		self.0.iter().map(|x| (x.0.as_str(), x.1.borrow())).collect()
 	}
+
+	/// Binds the arguments to the named parameters of `stmt` without allocating the intermediate slice
+	///
+	/// `rusqlite::Params` is sealed so `NamedParamSlice` can't be passed to `execute()` or `query()` directly, use this
+	/// together with `Statement::raw_execute()` or `Statement::raw_query()` instead:
+	///
+	/// ```
+	/// let connection = rusqlite::Connection::open_in_memory().unwrap();
+	/// let mut statement = connection.prepare("SELECT :a + :b").unwrap();
+	/// let values = std::collections::BTreeMap::from([("a", 1), ("b", 2)]);
+	/// let params = serde_rusqlite::to_params_named(values).unwrap();
+	/// params.bind(&mut statement).unwrap();
+	/// let sum: i64 = statement.raw_query().next().unwrap().unwrap().get(0).unwrap();
+	/// assert_eq!(sum, 3);
+	/// ```
+	///
+	/// Fails with `rusqlite::Error::InvalidParameterName` if `stmt` doesn't have a parameter with one of the names.
+	pub fn bind(&self, stmt: &mut rusqlite::Statement) -> crate::Result<()> {
+		for (name, value) in &self.0 {
+			let idx = stmt
+				.parameter_index(name)?
+				.ok_or_else(|| rusqlite::Error::InvalidParameterName(name.clone()))?;
+			stmt.raw_bind_parameter(idx, value)?;
+		}
+		Ok(())
+	}
+}
+
+impl Clone for NamedParamSlice {
+	fn clone(&self) -> Self {
+		Self(
+			self
+				.0
+				.iter()
+				.map(|(name, value)| (name.clone(), clone_param(value.as_ref())))
+				.collect(),
+		)
+	}
+}
+
+impl fmt::Debug for NamedParamSlice {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut out = f.debug_map();
+		for (name, value) in &self.0 {
+			match super::param_to_value(value.as_ref()) {
+				Ok(value) => out.entry(name, &value),
+				Err(e) => out.entry(name, &e),
+			};
+		}
+		out.finish()
+	}
+}
+
+/// Copies the output of `param` into an owned argument, the conversion errors are reported when the copy is bound
+fn clone_param(param: &dyn ToSql) -> Box<dyn ToSql> {
+	match param.to_sql() {
+		Ok(ToSqlOutput::Borrowed(value)) => Box::new(ToSqlOutput::Owned(value.into())),
+		Ok(ToSqlOutput::Owned(value)) => Box::new(ToSqlOutput::Owned(value)),
+		#[cfg(feature = "blob")]
+		Ok(ToSqlOutput::ZeroBlob(len)) => Box::new(ToSqlOutput::ZeroBlob(len)),
+		Ok(_) => Box::new(FailedParam(Error::ser_unsupported("special ToSqlOutput"))),
+		Err(e) => Box::new(FailedParam(Error::Serialization(e.to_string()))),
+	}
 }
 
 impl From<Vec<(String, Box<dyn rusqlite::types::ToSql>)>> for NamedParamSlice {
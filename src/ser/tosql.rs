@@ -1,9 +1,12 @@
 use rusqlite::types::{ToSql, Value};
 use serde::ser;
 
-use crate::{Error, Result};
+use crate::{Error, Result, SerializeOptions};
 
 use super::blob::BlobSerializer;
+#[cfg(feature = "serde_json")]
+use super::json::{json_error, json_text, JsonCompound, JsonValueSerializer};
+use super::raw::{self, RAW_VALUE_NEWTYPE};
 
 macro_rules! tosql_ser {
 	($fun:ident, &$type:ty) => {
@@ -18,18 +21,33 @@ macro_rules! tosql_ser {
 	};
 }
 
-pub struct ToSqlSerializer;
+#[derive(Default)]
+pub struct ToSqlSerializer {
+	pub options: SerializeOptions,
+}
 
 impl ser::Serializer for ToSqlSerializer {
 	type Ok = Box<dyn ToSql>;
 	type Error = Error;
 	type SerializeSeq = BlobSerializer;
-	type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTuple = BlobSerializer;
 	type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+	#[cfg(not(feature = "serde_json"))]
 	type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+	#[cfg(feature = "serde_json")]
+	type SerializeTupleVariant = JsonCompound<<JsonValueSerializer as ser::Serializer>::SerializeTupleVariant>;
+	#[cfg(not(feature = "serde_json"))]
 	type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+	#[cfg(feature = "serde_json")]
+	type SerializeMap = JsonCompound<<JsonValueSerializer as ser::Serializer>::SerializeMap>;
+	#[cfg(not(feature = "serde_json"))]
 	type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+	#[cfg(feature = "serde_json")]
+	type SerializeStruct = JsonCompound<<JsonValueSerializer as ser::Serializer>::SerializeStruct>;
+	#[cfg(not(feature = "serde_json"))]
 	type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+	#[cfg(feature = "serde_json")]
+	type SerializeStructVariant = JsonCompound<<JsonValueSerializer as ser::Serializer>::SerializeStructVariant>;
 
 	tosql_ser!(serialize_bool, bool);
 	tosql_ser!(serialize_i8, i8);
@@ -39,20 +57,41 @@ impl ser::Serializer for ToSqlSerializer {
 	tosql_ser!(serialize_u8, u8);
 	tosql_ser!(serialize_u16, u16);
 	tosql_ser!(serialize_u32, u32);
-	tosql_ser!(serialize_f64, f64);
 	tosql_ser!(serialize_str, &str);
 	tosql_ser!(serialize_bytes, &[u8]);
 
 	fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
-		if v > i64::MAX as u64 {
+		if v > i64::MAX as u64 && self.options.u64_as_text {
+			self.serialize_str(&v.to_string())
+		} else if v > i64::MAX as u64 {
 			Err(Error::ValueTooLarge(format!("Value is too large to fit into i64: {}", v)))
 		} else {
 			self.serialize_i64(v as i64)
 		}
 	}
 
+	fn serialize_i128(self, v: i128) -> Result<Self::Ok> {
+		self.serialize_str(&v.to_string())
+	}
+
+	fn serialize_u128(self, v: u128) -> Result<Self::Ok> {
+		self.serialize_str(&v.to_string())
+	}
+
 	fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
-		self.serialize_f64(f64::from(v))
+		if self.options.float_as_text && !v.is_nan() {
+			self.serialize_str(&v.to_string())
+		} else {
+			self.serialize_f64(f64::from(v))
+		}
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+		if self.options.float_as_text && !v.is_nan() {
+			self.serialize_str(&v.to_string())
+		} else {
+			Ok(Box::new(v))
+		}
 	}
 
 	fn serialize_char(self, v: char) -> Result<Self::Ok> {
@@ -76,11 +115,22 @@ impl ser::Serializer for ToSqlSerializer {
 		self.serialize_str(name)
 	}
 
-	fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<Self::Ok> {
-		self.serialize_str(variant)
+	fn serialize_unit_variant(self, _name: &'static str, variant_index: u32, variant: &'static str) -> Result<Self::Ok> {
+		if self.options.enum_as_index {
+			self.serialize_u32(variant_index)
+		} else {
+			self.serialize_str(variant)
+		}
 	}
 
-	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(self, _name: &'static str, value: &T) -> Result<Self::Ok> {
+	fn serialize_newtype_struct<T: ?Sized + serde::Serialize>(self, name: &'static str, value: &T) -> Result<Self::Ok> {
+		if name == RAW_VALUE_NEWTYPE {
+			let encoded = super::param_to_value(value.serialize(self)?.as_ref())?;
+			return match encoded {
+				Value::Blob(encoded) => Ok(Box::new(raw::decode(&encoded)?)),
+				_ => Err(Error::Serialization(format!("{} must contain bytes", RAW_VALUE_NEWTYPE))),
+			};
+		}
 		value.serialize(self)
 	}
 
@@ -91,21 +141,30 @@ impl ser::Serializer for ToSqlSerializer {
 		_variant: &'static str,
 		value: &T,
 	) -> Result<Self::Ok> {
+		#[cfg(feature = "serde_json")]
+		if self.options.compound_as_json {
+			return ser::Serializer::serialize_newtype_variant(JsonValueSerializer, name, _variant_index, _variant, value)
+				.map(json_text)
+				.map_err(json_error);
+		}
 		self.serialize_newtype_struct(name, value)
 	}
 
 	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-		Ok(BlobSerializer {
-			buf: Vec::with_capacity(len.unwrap_or(0)),
-		})
+		#[cfg(feature = "serde_json")]
+		if self.options.compound_as_json {
+			return BlobSerializer::json(len);
+		}
+		Ok(BlobSerializer::new(len))
 	}
 
-	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
-		Err(Error::ser_unsupported("tuple"))
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+		self.serialize_seq(Some(len))
 	}
 	fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
 		Err(Error::ser_unsupported("tuple_struct"))
 	}
+	#[cfg(not(feature = "serde_json"))]
 	fn serialize_tuple_variant(
 		self,
 		_name: &'static str,
@@ -115,12 +174,15 @@ impl ser::Serializer for ToSqlSerializer {
 	) -> Result<Self::SerializeTupleVariant> {
 		Err(Error::ser_unsupported("tuple_variant"))
 	}
+	#[cfg(not(feature = "serde_json"))]
 	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
 		Err(Error::ser_unsupported("map"))
 	}
+	#[cfg(not(feature = "serde_json"))]
 	fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
 		Err(Error::ser_unsupported("struct"))
 	}
+	#[cfg(not(feature = "serde_json"))]
 	fn serialize_struct_variant(
 		self,
 		_name: &'static str,
@@ -130,4 +192,53 @@ impl ser::Serializer for ToSqlSerializer {
 	) -> Result<Self::SerializeStructVariant> {
 		Err(Error::ser_unsupported("struct_variant"))
 	}
+
+	#[cfg(feature = "serde_json")]
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant> {
+		if !self.options.compound_as_json {
+			return Err(Error::ser_unsupported("tuple_variant"));
+		}
+		ser::Serializer::serialize_tuple_variant(JsonValueSerializer, name, variant_index, variant, len)
+			.map(|inner| JsonCompound { inner })
+			.map_err(json_error)
+	}
+	#[cfg(feature = "serde_json")]
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+		if !self.options.compound_as_json {
+			return Err(Error::ser_unsupported("map"));
+		}
+		ser::Serializer::serialize_map(JsonValueSerializer, len)
+			.map(|inner| JsonCompound { inner })
+			.map_err(json_error)
+	}
+	#[cfg(feature = "serde_json")]
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+		if !self.options.compound_as_json {
+			return Err(Error::ser_unsupported("struct"));
+		}
+		ser::Serializer::serialize_struct(JsonValueSerializer, name, len)
+			.map(|inner| JsonCompound { inner })
+			.map_err(json_error)
+	}
+	#[cfg(feature = "serde_json")]
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant> {
+		if !self.options.compound_as_json {
+			return Err(Error::ser_unsupported("struct_variant"));
+		}
+		ser::Serializer::serialize_struct_variant(JsonValueSerializer, name, variant_index, variant, len)
+			.map(|inner| JsonCompound { inner })
+			.map_err(json_error)
+	}
 }
@@ -1,9 +1,13 @@
 use std::{collections, fmt::Debug};
 
 use rusqlite::types::{ToSqlOutput, Value, ValueRef};
+use serde::{Deserialize as _, Serialize as _};
 use serde_derive::{Deserialize, Serialize};
 
-use crate::Error;
+use crate::{
+	BlobRepresentation, DeserializeOptions, Error, LazyParams, PositionalSliceSerializer, QueryAs, RowDeserializer,
+	SerializeOptions,
+};
 
 use super::to_params_named_with_fields;
 
@@ -95,6 +99,119 @@ fn test_uint() {
 	test_ser_err(&u64::MAX, |err| matches!(*err, super::Error::ValueTooLarge(..)));
 }
 
+#[test]
+fn test_nonzero() {
+	use std::num::{NonZeroI64, NonZeroU32, NonZeroU64};
+
+	test_value_same(
+		"INT CHECK(typeof(test_column) == 'integer')",
+		&NonZeroU32::new(98172983).unwrap(),
+	);
+	test_value_same("INT CHECK(typeof(test_column) == 'integer')", &NonZeroU64::new(1).unwrap());
+	test_value_same("INT CHECK(typeof(test_column) == 'integer')", &NonZeroI64::new(-5).unwrap());
+	test_value_same(
+		"INT CHECK(typeof(test_column) in ('integer', 'null'))",
+		&Some(NonZeroU64::new(i64::MAX as u64).unwrap()),
+	);
+	test_ser_err(&NonZeroU64::MAX, |err| matches!(*err, super::Error::ValueTooLarge(..)));
+
+	#[derive(Deserialize, Debug)]
+	#[allow(dead_code)]
+	struct Test {
+		f_integer: NonZeroU32,
+	}
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer) VALUES(0)", []).unwrap();
+	let res = con
+		.query_row("SELECT f_integer FROM test", [], |row| Ok(super::from_row::<Test>(row)))
+		.unwrap();
+	match res {
+		Err(e @ Error::Deserialization { .. }) => assert_eq!(
+			e.to_string(),
+			"Deserialization failed for column: f_integer error: invalid value: integer `0`, expected a nonzero u32"
+		),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_u64_as_text() {
+	let options = SerializeOptions::default().u64_as_text(true);
+	let con = make_connection_with_spec("test_column");
+	for value in [0, i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX] {
+		con.execute(
+			"INSERT INTO test VALUES(?)",
+			super::to_params_with_options(value, &options).unwrap(),
+		)
+		.unwrap();
+	}
+	let mut stmt = con
+		.prepare("SELECT test_column, typeof(test_column) FROM test ORDER BY rowid")
+		.unwrap();
+	let res = super::from_rows::<(u64, String)>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(
+		res,
+		[
+			(0, "integer".into()),
+			(i64::MAX as u64, "integer".into()),
+			(i64::MAX as u64 + 1, "text".into()),
+			(u64::MAX, "text".into()),
+		]
+	);
+	// default is still an error
+	assert!(matches!(super::to_params(u64::MAX), Err(Error::ValueTooLarge(_))));
+	let res = con
+		.query_row("SELECT '-1'", [], |row| Ok(super::from_row::<(u64,)>(row)))
+		.unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+	// TEXT in the INTEGER range is only read with numeric_from_text
+	let res = con
+		.query_row("SELECT '42'", [], |row| Ok(super::from_row::<(u64,)>(row)))
+		.unwrap();
+	assert!(res.is_err());
+	let res = con
+		.query_row("SELECT '42'", [], |row| {
+			Ok(super::from_row_with_options::<(u64,)>(
+				row,
+				&[],
+				&DeserializeOptions::default().numeric_from_text(true),
+			))
+		})
+		.unwrap();
+	assert_eq!(res, Ok((42,)));
+}
+
+#[test]
+fn test_int128() {
+	test_value_same("TEXT CHECK(typeof(test_column) == 'text')", &i128::MIN);
+	test_value_same(
+		"TEXT CHECK(test_column == '-170141183460469231731687303715884105728')",
+		&i128::MIN,
+	);
+	test_value_same(
+		"TEXT CHECK(test_column == '340282366920938463463374607431768211455')",
+		&u128::MAX,
+	);
+	test_value_same("TEXT CHECK(test_column == '-5')", &-5_i128);
+	test_value_same("TEXT CHECK(test_column == '0')", &0_u128);
+
+	let con = make_connection();
+	let res = con
+		.query_row("SELECT 42, 42", [], |row| Ok(super::from_row::<(i128, u128)>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, (42, 42));
+	for sql in [
+		"SELECT 'abc'",
+		"SELECT '340282366920938463463374607431768211456'",
+		"SELECT '-1'",
+	] {
+		let res = con.query_row(sql, [], |row| Ok(super::from_row::<(u128,)>(row))).unwrap();
+		assert!(matches!(res, Err(Error::Deserialization { .. })), "{}", sql);
+	}
+}
+
 #[test]
 fn test_float() {
 	test_value_same("REAL CHECK(typeof(test_column) == 'real')", &0.3_f32);
@@ -118,6 +235,39 @@ fn test_float() {
 	);
 }
 
+#[test]
+fn test_float_null() {
+	let con = make_connection();
+	// SQLite has no REAL NaN, binding NaN stores NULL
+	con.execute(
+		"INSERT INTO test(f_integer, f_real) VALUES(1, ?1)",
+		super::to_params(f32::NAN).unwrap(),
+	)
+	.unwrap();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real) VALUES(2, ?1)",
+		super::to_params(1.5_f32).unwrap(),
+	)
+	.unwrap();
+	let mut stmt = con
+		.prepare("SELECT f_real, f_real, f_real, f_real, typeof(f_real) FROM test ORDER BY f_integer")
+		.unwrap();
+	let res = stmt
+		.query_map([], |row| {
+			Ok(super::from_row::<(Option<f32>, f32, Option<f64>, f64, String)>(row).unwrap())
+		})
+		.unwrap()
+		.map(|res| res.unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(res[0].0, None);
+	assert!(res[0].1.is_nan());
+	assert_eq!(res[0].2, None);
+	assert!(res[0].3.is_nan());
+	assert_eq!(res[0].4, "null");
+	assert_eq!((res[1].0, res[1].1, res[1].2, res[1].3), (Some(1.5), 1.5, Some(1.5), 1.5));
+	assert_eq!(res[1].4, "real");
+}
+
 #[test]
 fn test_string() {
 	test_value_same("TEXT CHECK(typeof(test_column) == 'text')", &'a');
@@ -164,6 +314,71 @@ fn test_enum() {
 	}
 }
 
+#[test]
+fn test_enum_from_integer() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	enum Test {
+		A,
+		B,
+		C,
+	}
+
+	let con = make_connection();
+	let res = con
+		.query_row("SELECT 0, 2, 'B'", [], |row| {
+			Ok(super::from_row::<(Test, Test, Test)>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res, (Test::A, Test::C, Test::B));
+	for sql in ["SELECT 3", "SELECT -1"] {
+		let res = con.query_row(sql, [], |row| Ok(super::from_row::<(Test,)>(row))).unwrap();
+		assert!(matches!(res, Err(Error::Deserialization { .. })), "{}", sql);
+	}
+}
+
+#[test]
+fn test_enum_as_index() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	enum Status {
+		Active,
+		Blocked,
+		Deleted,
+	}
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		status: Status,
+	}
+
+	let con = make_connection_with_spec("status INT CHECK(typeof(status) == 'integer')");
+	let params = (Status::Deleted,)
+		.serialize(PositionalSliceSerializer::with_enum_as_index())
+		.unwrap();
+	con.execute("INSERT INTO test VALUES(?)", rusqlite::params_from_iter(params))
+		.unwrap();
+	let params = Test { status: Status::Blocked }
+		.serialize(super::NamedSliceSerializer::with_enum_as_index())
+		.unwrap();
+	con.execute("INSERT INTO test VALUES(:status)", params.to_slice().as_slice())
+		.unwrap();
+	let mut stmt = con.prepare("SELECT status FROM test ORDER BY rowid").unwrap();
+	let stored = stmt
+		.query_map([], |row| row.get::<_, i64>(0))
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(stored, [2, 1]);
+	let res = super::from_rows::<Test>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [Test { status: Status::Deleted }, Test { status: Status::Blocked }]);
+
+	// the default is still TEXT
+	assert_eq!(
+		super::to_value_vec((Status::Active,)).unwrap(),
+		[Value::Text("Active".into())]
+	);
+}
+
 #[test]
 fn test_map() {
 	{
@@ -273,6 +488,194 @@ fn test_tuple() {
 	}
 }
 
+#[test]
+fn test_tuple_column_count_mismatch() {
+	let con = make_connection();
+	let res = con
+		.query_row("SELECT 1, 2, 3", [], |row| {
+			Ok(super::from_row::<(i64, i64, i64, i64, i64)>(row))
+		})
+		.unwrap();
+	match res {
+		Err(
+			e @ Error::ColumnCountMismatch {
+				expected: 5,
+				found: 3,
+				row: None,
+			},
+		) => {
+			assert_eq!(
+				e.to_string(),
+				"Column count mismatch: expected 5 column(s), but the row has 3"
+			)
+		}
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	// extra columns are still ignored
+	let res = con
+		.query_row("SELECT 1, 2, 3", [], |row| Ok(super::from_row::<(i64, i64)>(row)))
+		.unwrap();
+	assert_eq!(res.unwrap(), (1, 2));
+}
+
+#[test]
+fn test_error_eq() {
+	#[derive(Deserialize, Debug)]
+	struct Test {
+		#[allow(dead_code)]
+		f_integer: i64,
+	}
+
+	let con = make_connection();
+	let res = con
+		.query_row("SELECT NULL AS f_integer", [], |row| Ok(super::from_row::<Test>(row)))
+		.unwrap();
+	assert_eq!(
+		res.unwrap_err(),
+		Error::UnexpectedNull {
+			column: Some("f_integer".into()),
+			row: None,
+		}
+	);
+	let res = con
+		.query_row("SELECT 1", [], |row| Ok(super::from_row::<(i64, i64)>(row)))
+		.unwrap();
+	assert_eq!(
+		res.unwrap_err(),
+		Error::ColumnCountMismatch {
+			expected: 2,
+			found: 1,
+			row: None,
+		}
+	);
+	assert_eq!(
+		Error::from(rusqlite::Error::QueryReturnedNoRows),
+		Error::Rusqlite(rusqlite::Error::QueryReturnedNoRows)
+	);
+	assert_ne!(Error::ColumnNamesNotAvailable, Error::Serialization("".into()));
+
+	// errors with a boxed source are compared by their text
+	let boxed = || {
+		Error::Rusqlite(rusqlite::Error::FromSqlConversionFailure(
+			0,
+			rusqlite::types::Type::Text,
+			Box::new(Error::Serialization("boxed".into())),
+		))
+	};
+	let error = boxed();
+	assert_eq!(error, error);
+	assert_eq!(boxed(), boxed());
+	assert_ne!(boxed(), Error::Rusqlite(rusqlite::Error::QueryReturnedNoRows));
+	let in_row = |row| Error::InRow {
+		row,
+		source: Box::new(boxed()),
+	};
+	assert_eq!(in_row(1), in_row(1));
+	assert_ne!(in_row(1), in_row(2));
+}
+
+#[test]
+fn test_error_into_rusqlite() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, 'one')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+	let res = stmt
+		.query_map([], |row| Ok(super::from_row::<(i64, String)>(row)?))
+		.unwrap()
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.unwrap();
+	assert_eq!(res, [(1, "one".to_string())]);
+
+	let res = stmt.query_row([], |row| Ok(super::from_row::<(i64, i64)>(row)?)).unwrap_err();
+	match res {
+		rusqlite::Error::ToSqlConversionFailure(e) => {
+			assert!(e.to_string().starts_with("Deserialization failed for column: f_text"), "{e}");
+			assert!(matches!(e.downcast_ref::<Error>(), Some(Error::Deserialization { .. })));
+		}
+		e => panic!("unexpected error: {e}"),
+	}
+	// rusqlite errors are passed through
+	assert_eq!(
+		rusqlite::Error::from(Error::Rusqlite(rusqlite::Error::QueryReturnedNoRows)),
+		rusqlite::Error::QueryReturnedNoRows
+	);
+}
+
+#[test]
+fn test_borrowed_str() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Named<'a> {
+		f_integer: i64,
+		f_text: &'a str,
+		f_null: Option<&'a str>,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, 'first'), (2, 'second')", [])
+		.unwrap();
+	let mut stmt = con
+		.prepare("SELECT f_integer, f_text, f_null FROM test ORDER BY f_integer")
+		.unwrap();
+	let mut rows = stmt.query([]).unwrap();
+	let mut names = vec![];
+	while let Some(row) = rows.next().unwrap() {
+		let res = super::from_row_borrowed::<Named>(row).unwrap();
+		assert_eq!(res.f_null, None);
+		names.push((res.f_integer, res.f_text.to_owned()));
+	}
+	assert_eq!(names, [(1, "first".to_string()), (2, "second".to_string())]);
+
+	// only TEXT can be borrowed as str
+	let mut stmt = con
+		.prepare("SELECT f_integer, f_integer AS f_text, f_null FROM test")
+		.unwrap();
+	let mut rows = stmt.query([]).unwrap();
+	let row = rows.next().unwrap().unwrap();
+	assert!(matches!(
+		super::from_row_borrowed::<Named>(row),
+		Err(Error::Deserialization { column: Some(column), .. }) if column == "f_text"
+	));
+}
+
+#[test]
+fn test_borrowed_bytes() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Blobs<'a> {
+		borrowed: &'a [u8],
+		#[serde(borrow)]
+		bytes: &'a serde_bytes::Bytes,
+		#[serde(with = "serde_bytes")]
+		owned: Vec<u8>,
+		text: &'a [u8],
+	}
+
+	let con = make_connection();
+	let mut stmt = con
+		.prepare("SELECT x'0102' AS borrowed, x'03' AS bytes, x'0405' AS owned, 'abc' AS text")
+		.unwrap();
+	let mut rows = stmt.query([]).unwrap();
+	let row = rows.next().unwrap().unwrap();
+	let res = super::from_row_borrowed::<Blobs>(row).unwrap();
+	assert_eq!(
+		res,
+		Blobs {
+			borrowed: &[1, 2],
+			bytes: serde_bytes::Bytes::new(&[3]),
+			owned: vec![4, 5],
+			text: b"abc",
+		}
+	);
+
+	// max_blob_len is still checked
+	let columns = super::columns_from_statement(row.as_ref());
+	let options = DeserializeOptions::default().max_blob_len(Some(1));
+	assert!(matches!(
+		Blobs::deserialize(RowDeserializer::from_row_with_options(row, &columns, options)),
+		Err(Error::Deserialization { column: Some(column), .. }) if column == "borrowed"
+	));
+}
+
 #[test]
 fn test_struct() {
 	{
@@ -532,3 +935,3782 @@ fn pluck_named() {
 		sqlified
 	);
 }
+
+#[test]
+fn test_values_named() {
+	#[derive(Serialize)]
+	struct Food {
+		id: i32,
+		name: String,
+		weight: Option<f64>,
+	}
+
+	let item = Food {
+		id: 15,
+		name: "Snickers bar".to_string(),
+		weight: None,
+	};
+	assert_eq!(
+		super::to_values_named(&item).unwrap(),
+		[
+			(":id".to_string(), Value::Integer(15)),
+			(":name".to_string(), Value::Text("Snickers bar".into())),
+			(":weight".to_string(), Value::Null),
+		]
+	);
+	let options = SerializeOptions::default().named_prefix('@').skip_none(true);
+	assert_eq!(
+		super::to_values_named_with_options(&item, &options).unwrap(),
+		[
+			("@id".to_string(), Value::Integer(15)),
+			("@name".to_string(), Value::Text("Snickers bar".into())),
+		]
+	);
+}
+
+#[test]
+fn test_rowid() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_text: String,
+	}
+	let con = make_connection();
+	let src = [
+		Test {
+			f_integer: 10,
+			f_text: "first".into(),
+		},
+		Test {
+			f_integer: 20,
+			f_text: "second".into(),
+		},
+	];
+	for row in &src {
+		con.execute(
+			"INSERT INTO test(f_integer, f_text) VALUES(:f_integer, :f_text)",
+			super::to_params_named(row).unwrap().to_slice().as_slice(),
+		)
+		.unwrap();
+	}
+	// explicit rowid column
+	{
+		let mut stmt = con.prepare("SELECT rowid, f_integer, f_text FROM test").unwrap();
+		let res = super::from_rows_with_rowid::<Test>(stmt.query([]).unwrap())
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(res, vec![(1, src[0].clone()), (2, src[1].clone())]);
+	}
+	// rowid column isn't passed to the tuple
+	{
+		let mut stmt = con.prepare("SELECT ROWID, f_integer, f_text FROM test").unwrap();
+		let mut res = super::from_rows_with_rowid::<(i64, String)>(stmt.query([]).unwrap());
+		assert_eq!(res.next().unwrap().unwrap(), (1, (10, "first".to_string())));
+	}
+	// first column is an alias
+	{
+		let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+		let mut res = super::from_rows_with_rowid::<Test>(stmt.query([]).unwrap());
+		assert_eq!(res.next().unwrap().unwrap(), (10, src[0].clone()));
+	}
+	// first column is not an integer
+	{
+		let mut stmt = con.prepare("SELECT f_text, f_integer FROM test").unwrap();
+		let mut res = super::from_rows_with_rowid::<Test>(stmt.query([]).unwrap());
+		match res.next().unwrap() {
+			Err(Error::InRow { row: 0, source }) => assert!(matches!(*source, Error::Rusqlite(_))),
+			res => panic!("Unexpected result: {:?}", res),
+		}
+	}
+}
+
+#[test]
+fn test_float_as_text() {
+	fn test_round_trip<T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + Debug>(value: T) {
+		let con = make_connection_with_spec("test_column TEXT CHECK(typeof(test_column) == 'text')");
+		let params = value
+			.serialize(PositionalSliceSerializer::with_options(
+				SerializeOptions::default().float_as_text(true),
+			))
+			.unwrap();
+		con.execute("INSERT INTO test(test_column) VALUES(?)", rusqlite::params_from_iter(params))
+			.unwrap();
+		let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+		let columns = super::columns_from_statement(&stmt);
+		let res = stmt
+			.query_row([], |row| {
+				Ok(T::deserialize(RowDeserializer::from_row_with_options(
+					row,
+					&columns,
+					DeserializeOptions::default().float_from_text(true),
+				)))
+			})
+			.unwrap()
+			.unwrap();
+		assert_eq!(res, value);
+	}
+
+	test_round_trip(0.1_f64);
+	test_round_trip(0.1_f64 + 0.2_f64);
+	test_round_trip(-1.0e-300_f64);
+	test_round_trip(f64::MAX);
+	test_round_trip(f64::INFINITY);
+	test_round_trip(0.3_f32);
+	test_round_trip(f32::MIN_POSITIVE);
+	test_round_trip(Some(1.0_f64 / 3.0));
+
+	// text is only parsed into a float if requested
+	let con = make_connection_with_spec("test_column TEXT");
+	con.execute("INSERT INTO test(test_column) VALUES('0.1')", []).unwrap();
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	assert!(stmt.query_row([], |row| Ok(super::from_row::<f64>(row))).unwrap().is_err());
+}
+
+#[test]
+fn test_query_as() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_text: String,
+	}
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'first'), (2, 'second'), (3, 'third')")
+		.unwrap();
+	// positional parameters
+	let res = con
+		.query_as::<Test, _>(
+			"SELECT f_integer, f_text FROM test WHERE f_integer >= ? ORDER BY f_integer",
+			(2,),
+		)
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(
+		res,
+		vec![
+			Test {
+				f_integer: 2,
+				f_text: "second".into()
+			},
+			Test {
+				f_integer: 3,
+				f_text: "third".into()
+			},
+		]
+	);
+	// named parameters
+	let mut params = collections::HashMap::new();
+	params.insert("f_text", "first");
+	let mut res = con
+		.query_as::<(i64, String), _>("SELECT f_integer, f_text FROM test WHERE f_text = :f_text", params)
+		.unwrap();
+	assert_eq!(res.next().unwrap().unwrap(), (1, "first".to_string()));
+	assert!(res.next().is_none());
+	// numbered positional parameters
+	let mut res = con
+		.query_as::<(i64,), _>("SELECT f_integer FROM test WHERE f_integer = ?1", (3,))
+		.unwrap();
+	assert_eq!(res.next().unwrap().unwrap(), (3,));
+	assert!(res.next().is_none());
+	// invalid query
+	assert!(matches!(
+		con.query_as::<Test, _>("SELECT * FROM missing", ()),
+		Err(Error::Rusqlite(_))
+	));
+}
+
+#[test]
+fn test_query_deserialize() {
+	fn load_texts(con: &rusqlite::Connection, min: i64) -> Result<super::DeserStatementRows<'_, String>, Error> {
+		super::query_deserialize(
+			con,
+			"SELECT f_text FROM test WHERE f_integer >= :min ORDER BY f_integer",
+			collections::BTreeMap::from([("min", min)]),
+		)
+	}
+
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'first'), (2, 'second'), (3, 'third')")
+		.unwrap();
+	let res = load_texts(&con, 2).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+	assert_eq!(res, ["second", "third"]);
+	// numbered placeholders are bound positionally
+	let res = super::query_deserialize::<String, _>(&con, "SELECT f_text FROM test WHERE f_integer = ?1", (1,))
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, ["first"]);
+	assert!(matches!(
+		super::query_deserialize::<String, _>(&con, "SELECT * FROM missing", ()),
+		Err(Error::Rusqlite(_))
+	));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono() {
+	use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Date(#[serde(with = "crate::chrono::naive_date")] NaiveDate);
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Time(#[serde(with = "crate::chrono::naive_time")] NaiveTime);
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct DateTime(#[serde(with = "crate::chrono::naive_datetime")] NaiveDateTime);
+
+	let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+	test_value_same("TEXT CHECK(test_column == '2024-02-29')", &Date(date));
+	let time = NaiveTime::from_hms_opt(13, 5, 9).unwrap();
+	test_value_same("TEXT CHECK(test_column == '13:05:09')", &Time(time));
+	let time = NaiveTime::from_hms_milli_opt(23, 59, 1, 250).unwrap();
+	test_value_same("TEXT CHECK(test_column == '23:59:01.250')", &Time(time));
+	let time = NaiveTime::from_hms_nano_opt(0, 0, 0, 123_456_789).unwrap();
+	test_value_same("TEXT CHECK(test_column == '00:00:00.123456789')", &Time(time));
+	test_value_same(
+		"TEXT CHECK(test_column == '2024-02-29 23:59:01.250')",
+		&DateTime(date.and_time(NaiveTime::from_hms_milli_opt(23, 59, 1, 250).unwrap())),
+	);
+	// values produced by SQLite functions
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		#[serde(with = "crate::chrono::naive_date")]
+		date: NaiveDate,
+		#[serde(with = "crate::chrono::naive_time")]
+		time: NaiveTime,
+		#[serde(with = "crate::chrono::naive_datetime")]
+		datetime: NaiveDateTime,
+	}
+	let con = make_connection_with_spec("test_column TEXT");
+	let mut stmt = con
+		.prepare("SELECT date('2024-02-29') AS date, time('13:05') AS time, datetime('2024-02-29T13:05:09') AS datetime")
+		.unwrap();
+	let res = stmt.query_row([], |row| Ok(super::from_row::<Test>(row))).unwrap();
+	assert_eq!(
+		res.unwrap(),
+		Test {
+			date,
+			time: NaiveTime::from_hms_opt(13, 5, 0).unwrap(),
+			datetime: date.and_hms_opt(13, 5, 9).unwrap(),
+		}
+	);
+	// invalid text
+	let mut stmt = con.prepare("SELECT '2024-02-30'").unwrap();
+	let res = stmt.query_row([], |row| Ok(super::from_row::<Date>(row))).unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+}
+
+#[test]
+fn test_btreemap_order() {
+	let mut src = collections::BTreeMap::new();
+	src.insert("c", 3);
+	src.insert("a", 1);
+	src.insert("b", 2);
+	let params = super::to_params_named(&src).unwrap();
+	assert_eq!(
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+		[":a", ":b", ":c"]
+	);
+
+	let con = make_connection_with_spec("a INT, b INT, c INT");
+	con.execute("INSERT INTO test VALUES(:a, :b, :c)", params.to_slice().as_slice())
+		.unwrap();
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<collections::BTreeMap<String, i64>>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res.into_iter().collect::<Vec<_>>(),
+		[("a".into(), 1), ("b".into(), 2), ("c".into(), 3)]
+	);
+}
+
+#[test]
+fn test_default_missing_fields() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Inner {
+		a: i64,
+		b: Option<String>,
+	}
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_real: f64,
+		f_text: String,
+		f_blob: Vec<u8>,
+		f_null: Option<i64>,
+		flag: bool,
+		pair: (u8, char),
+		inner: Inner,
+	}
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(5, 'text')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	// missing non-optional fields are an error by default
+	let res = stmt.query_row([], |row| Ok(super::from_row::<Test>(row))).unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+	// and receive default values with the option
+	let res = stmt
+		.query_row([], |row| {
+			Ok(Test::deserialize(RowDeserializer::from_row_with_options(
+				row,
+				&columns,
+				DeserializeOptions::default().default_missing_fields(true),
+			)))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		Test {
+			f_integer: 5,
+			f_real: 0.,
+			f_text: "text".into(),
+			f_blob: vec![],
+			f_null: None,
+			flag: false,
+			pair: (0, '\0'),
+			inner: Inner { a: 0, b: None },
+		}
+	);
+}
+
+#[test]
+fn test_params_lazy() {
+	fn to_values(params: impl IntoIterator<Item = Box<dyn rusqlite::ToSql>>) -> Vec<Value> {
+		params
+			.into_iter()
+			.map(|param| match param.to_sql().unwrap() {
+				ToSqlOutput::Borrowed(val) => val.into(),
+				ToSqlOutput::Owned(val) => val,
+				_ => unreachable!(),
+			})
+			.collect()
+	}
+
+	let src = (34, 76.4, "the test", vec![10_u8, 20, 30], None::<i64>);
+	assert_eq!(
+		to_values(LazyParams::new(&src, SerializeOptions::default())),
+		to_values(src.serialize(PositionalSliceSerializer::default()).unwrap()),
+	);
+	assert_eq!(
+		to_values(LazyParams::new(15, SerializeOptions::default())),
+		[Value::Integer(15)]
+	);
+	assert!(to_values(LazyParams::new(Vec::<i64>::new(), SerializeOptions::default())).is_empty());
+
+	let con = make_connection();
+	con.execute("INSERT INTO test VALUES(?, ?, ?, ?, ?)", super::to_params_lazy(&src))
+		.unwrap();
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<(i64, f64, String, Vec<u8>, Option<i64>)>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, (34, 76.4, "the test".to_string(), vec![10, 20, 30], None));
+	// serialization error is reported during binding
+	let res = con.execute(
+		"INSERT INTO test VALUES(?, ?, ?, ?, ?)",
+		super::to_params_lazy((1, 2, u64::MAX, 4, 5)),
+	);
+	assert!(matches!(res, Err(rusqlite::Error::ToSqlConversionFailure(_))));
+}
+
+#[test]
+fn test_visit_row() {
+	use rusqlite::types::Type;
+
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test VALUES(?, ?, ?, ?, ?)",
+		super::to_params((1, 2.5, "text", vec![1_u8, 2], None::<i64>)).unwrap(),
+	)
+	.unwrap();
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let mut visited = vec![];
+	stmt
+		.query_row([], |row| {
+			super::visit_row(row, &columns, |name, value| {
+				visited.push((name.to_string(), value.data_type()))
+			})
+			.unwrap();
+			Ok(())
+		})
+		.unwrap();
+	assert_eq!(
+		visited,
+		[
+			("f_integer".to_string(), Type::Integer),
+			("f_real".to_string(), Type::Real),
+			("f_text".to_string(), Type::Text),
+			("f_blob".to_string(), Type::Blob),
+			("f_null".to_string(), Type::Null),
+		]
+	);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_time_delta() {
+	use chrono::TimeDelta;
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Seconds(#[serde(with = "crate::chrono::time_delta_seconds")] TimeDelta);
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Millis(#[serde(with = "crate::chrono::time_delta_millis")] TimeDelta);
+
+	for secs in [0, 90, -3600, 86400 * 365 * 1000] {
+		test_value_same(
+			&format!("INT CHECK(test_column == {})", secs),
+			&Seconds(TimeDelta::seconds(secs)),
+		);
+		test_value_same(
+			&format!("INT CHECK(test_column == {})", secs * 1000),
+			&Millis(TimeDelta::seconds(secs)),
+		);
+	}
+	test_value_same("INT CHECK(test_column == -1500)", &Millis(TimeDelta::milliseconds(-1500)));
+	// sub-unit part is truncated
+	test_values(
+		"INT CHECK(test_column == 1)",
+		&Seconds(TimeDelta::milliseconds(1999)),
+		&Seconds(TimeDelta::seconds(1)),
+	);
+	test_values(
+		"INT CHECK(test_column == 2)",
+		&Millis(TimeDelta::microseconds(2500)),
+		&Millis(TimeDelta::milliseconds(2)),
+	);
+	// out of range
+	let con = make_connection_with_spec("test_column INT");
+	let mut stmt = con.prepare("SELECT 9223372036854775807").unwrap();
+	let res = stmt.query_row([], |row| Ok(super::from_row::<Seconds>(row))).unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_naive_datetime_julian_day() {
+	use chrono::{NaiveDate, NaiveDateTime};
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct JulianDay(#[serde(with = "crate::chrono::naive_datetime_julian_day")] NaiveDateTime);
+
+	let datetime = |text| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").unwrap();
+	for (text, julian_day) in [
+		("1970-01-01 00:00:00", 2440587.5),
+		("2000-01-01 12:00:00", 2451545.),
+		("2024-02-29 06:30:15.250", 2460369.7710098377),
+		("1582-10-15 00:00:00", 2299160.5),
+	] {
+		test_value_same(
+			&format!(
+				"REAL CHECK(test_column == julianday('{0}') AND test_column == {1})",
+				text, julian_day
+			),
+			&JulianDay(datetime(text)),
+		);
+	}
+	// sub-millisecond part is truncated
+	test_values(
+		"REAL CHECK(test_column == julianday('2000-01-01 12:00:00.001'))",
+		&JulianDay(datetime("2000-01-01 12:00:00.001999")),
+		&JulianDay(datetime("2000-01-01 12:00:00.001")),
+	);
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Pair {
+		#[serde(with = "crate::chrono::naive_datetime_julian_day")]
+		real: NaiveDateTime,
+		#[serde(with = "crate::chrono::naive_datetime_julian_day")]
+		integer: NaiveDateTime,
+	}
+	let con = make_connection_with_spec("test_column INT");
+	let res = con
+		.query_row("SELECT julianday('2000-01-01') AS real, 2451545 AS integer", [], |row| {
+			Ok(super::from_row::<Pair>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(
+		res,
+		Pair {
+			real: NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().into(),
+			integer: datetime("2000-01-01 12:00:00"),
+		}
+	);
+	for sql in [
+		"SELECT -1. AS real, 0 AS integer",
+		"SELECT 1e300 AS real, 0 AS integer",
+		"SELECT 'text' AS real, 0 AS integer",
+	] {
+		let res = con.query_row(sql, [], |row| Ok(super::from_row::<Pair>(row))).unwrap();
+		assert!(matches!(res, Err(Error::Deserialization { .. })), "{}", sql);
+	}
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_time_delta_iso8601() {
+	use chrono::TimeDelta;
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Iso(#[serde(with = "crate::chrono::time_delta_iso8601")] TimeDelta);
+
+	for (text, value) in [
+		("PT0S", TimeDelta::zero()),
+		("PT1H30M", TimeDelta::minutes(90)),
+		("P1DT2.5S", TimeDelta::days(1) + TimeDelta::milliseconds(2500)),
+		("P3D", TimeDelta::days(3)),
+		("-PT0.000001S", TimeDelta::microseconds(-1)),
+	] {
+		test_value_same(&format!("TEXT CHECK(test_column == '{}')", text), &Iso(value));
+	}
+	// alternative forms are accepted during deserialization
+	let con = make_connection_with_spec("test_column TEXT");
+	for (text, value) in [
+		("P2W", TimeDelta::weeks(2)),
+		("PT36H", TimeDelta::hours(36)),
+		("PT1.50S", TimeDelta::milliseconds(1500)),
+	] {
+		let res = con
+			.query_row(&format!("SELECT '{}'", text), [], |row| {
+				Ok(super::from_row::<Iso>(row).unwrap())
+			})
+			.unwrap();
+		assert_eq!(res, Iso(value));
+	}
+	for text in ["", "P", "PT", "1H", "P1Y", "P1M", "PT1.5H", "PT-1S", "PT1S1S"] {
+		let res = con
+			.query_row(&format!("SELECT '{}'", text), [], |row| Ok(super::from_row::<Iso>(row)))
+			.unwrap();
+		assert!(matches!(res, Err(Error::Deserialization { .. })), "{}", text);
+	}
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_date_time_fixed_offset() {
+	use chrono::{DateTime, FixedOffset, NaiveDate, Timelike};
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Fixed(#[serde(with = "crate::chrono::date_time_fixed_offset")] DateTime<FixedOffset>);
+
+	let naive = NaiveDate::from_ymd_opt(2024, 3, 1)
+		.unwrap()
+		.and_hms_milli_opt(12, 30, 0, 250)
+		.unwrap();
+	for (text, offset) in [
+		(
+			"2024-03-01T12:30:00.250+05:30",
+			FixedOffset::east_opt(5 * 3600 + 1800).unwrap(),
+		),
+		("2024-03-01T12:30:00.250-08:00", FixedOffset::west_opt(8 * 3600).unwrap()),
+		("2024-03-01T12:30:00.250+00:00", FixedOffset::east_opt(0).unwrap()),
+	] {
+		let value = naive.and_local_timezone(offset).unwrap();
+		test_value_same(&format!("TEXT CHECK(test_column == '{}')", text), &Fixed(value));
+	}
+
+	let con = make_connection_with_spec("test_column TEXT");
+	let res = con
+		.query_row("SELECT '2024-03-01 12:30:00-02:00'", [], |row| {
+			Ok(super::from_row::<Fixed>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.0.offset().local_minus_utc(), -2 * 3600);
+	assert_eq!(res.0.naive_local(), naive.with_nanosecond(0).unwrap());
+	let res = con
+		.query_row("SELECT '2024-03-01 12:30:00'", [], |row| Ok(super::from_row::<Fixed>(row)))
+		.unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+}
+
+#[test]
+#[cfg(feature = "blob")]
+fn test_blob_param_from_reader() {
+	#[derive(Serialize)]
+	struct File {
+		name: String,
+	}
+
+	let con = make_connection_with_spec("name TEXT, data BLOB");
+	let data = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+	let blob = super::blob_param_from_reader(data.as_slice(), data.len()).unwrap();
+	let mut params = super::to_params_named(File { name: "large".into() }).unwrap();
+	params.push((":data".into(), blob.param()));
+	con.execute("INSERT INTO test VALUES (:name, :data)", params.to_slice().as_slice())
+		.unwrap();
+	blob.write_into(&con, "test", "data", con.last_insert_rowid()).unwrap();
+	let res: Vec<u8> = con.query_row("SELECT data FROM test", [], |row| row.get(0)).unwrap();
+	assert_eq!(res, data);
+
+	// reader is shorter than the declared length
+	let blob = super::blob_param_from_reader(&data[..10], 20).unwrap();
+	con.execute("INSERT INTO test VALUES ('short', ?)", [&blob]).unwrap();
+	let res = blob.write_into(&con, "test", "data", con.last_insert_rowid());
+	assert!(matches!(res, Err(Error::Serialization(_))));
+
+	assert!(matches!(
+		super::blob_param_from_reader(&data[..], i32::MAX as usize + 1),
+		Err(Error::ValueTooLarge(_))
+	));
+}
+
+#[test]
+fn test_from_row_split() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct User {
+		id: i64,
+		name: String,
+	}
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Address {
+		id: i64,
+		city: String,
+	}
+
+	let con = rusqlite::Connection::open_in_memory().unwrap();
+	con.execute_batch(
+		"CREATE TABLE user(id INT, name TEXT, address_id INT);
+		CREATE TABLE address(id INT, city TEXT);
+		INSERT INTO user VALUES (1, 'alice', 10), (2, 'bob', 20);
+		INSERT INTO address VALUES (10, 'Paris'), (20, 'Oslo');",
+	)
+	.unwrap();
+	let mut stmt = con
+		.prepare(
+			"SELECT user.id, user.name, address.id, address.city FROM user JOIN address ON address.id = address_id ORDER BY user.id",
+		)
+		.unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_and_then([], |row| super::from_row_split::<User, Address>(row, &columns, 2))
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(
+		res,
+		[
+			(
+				User {
+					id: 1,
+					name: "alice".into()
+				},
+				Address {
+					id: 10,
+					city: "Paris".into()
+				}
+			),
+			(
+				User {
+					id: 2,
+					name: "bob".into()
+				},
+				Address {
+					id: 20,
+					city: "Oslo".into()
+				}
+			),
+		]
+	);
+
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_split::<User, Address>(row, &columns, 5)))
+		.unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+}
+
+#[test]
+fn test_rows_filtered() {
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'one'), (2, 'two'), (3, 'three'), (4, 'four')")
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+	let res = super::from_rows_filtered::<(i64, String), _>(stmt.query([]).unwrap(), |row| row.get::<_, i64>(0).unwrap() % 2 == 0)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [(2, "two".to_string()), (4, "four".to_string())]);
+}
+
+#[test]
+fn test_params_named_each() {
+	#[derive(Serialize)]
+	struct Test {
+		f_integer: i64,
+		f_text: &'static str,
+	}
+	let src = vec![
+		Test {
+			f_integer: 1,
+			f_text: "first",
+		},
+		Test {
+			f_integer: 2,
+			f_text: "second",
+		},
+		Test {
+			f_integer: 3,
+			f_text: "third",
+		},
+	];
+	let con = make_connection();
+	let params = super::to_params_named_each(&src).unwrap();
+	assert_eq!(params.len(), 3);
+	let mut stmt = con
+		.prepare("INSERT INTO test(f_integer, f_text) VALUES(:f_integer, :f_text)")
+		.unwrap();
+	for params in &params {
+		assert_eq!(stmt.execute(params.to_slice().as_slice()).unwrap(), 1);
+	}
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+	let res = super::from_rows::<(i64, String)>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [(1, "first".into()), (2, "second".into()), (3, "third".into())]);
+	// error in any of the items fails the whole serialization
+	assert!(super::to_params_named_each([Some(1)]).is_err());
+}
+
+#[test]
+fn test_integer_into_float() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer) VALUES(2), (3)", []).unwrap();
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Totals {
+		total: f64,
+		max: f32,
+		missing: f64,
+	}
+	// SUM() over INTEGER column returns INTEGER
+	let res = con
+		.query_row(
+			"SELECT SUM(f_integer) AS total, MAX(f_integer) AS max, NULL AS missing FROM test",
+			[],
+			|row| Ok(super::from_row::<Totals>(row)),
+		)
+		.unwrap()
+		.unwrap();
+	assert_eq!(res.total, 5.);
+	assert_eq!(res.max, 3.);
+	assert!(res.missing.is_nan());
+	let res = con
+		.query_row("SELECT SUM(f_integer) FROM test", [], |row| Ok(super::from_row::<f64>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, 5.);
+}
+
+#[test]
+fn test_numeric_from_text() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_real, f_text) VALUES('12', '2.5', '-3')", [])
+		.unwrap();
+
+	#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+	struct Numbers {
+		f_integer: u16,
+		f_real: f64,
+		f_text: i64,
+	}
+	let expected = Numbers {
+		f_integer: 12,
+		f_real: 2.5,
+		f_text: -3,
+	};
+	let query = "SELECT CAST(f_integer AS TEXT) AS f_integer, CAST(f_real AS TEXT) AS f_real, f_text FROM test";
+
+	// strict by default
+	let res = con.query_row(query, [], |row| Ok(super::from_row::<Numbers>(row))).unwrap();
+	assert!(res.is_err());
+
+	let options = DeserializeOptions::default().numeric_from_text(true);
+	let from_row = |row: &rusqlite::Row| {
+		let columns = super::columns_from_statement(row.as_ref());
+		Ok(super::from_row_with_options::<Numbers>(row, &columns, &options))
+	};
+	let res = con.query_row(query, [], from_row).unwrap();
+	assert_eq!(res.unwrap(), expected);
+	let mut stmt = con.prepare(query).unwrap();
+	let res = super::from_rows::<Numbers>(stmt.query([]).unwrap())
+		.with_options(options)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, vec![expected]);
+	let mut rows = stmt.query([]).unwrap();
+	let res = super::from_rows_ref::<Numbers>(&mut rows)
+		.with_options(options)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, vec![expected]);
+	drop(rows);
+	let res = super::from_rows_filtered::<Numbers, _>(stmt.query([]).unwrap(), |_| true)
+		.with_options(options)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, vec![expected]);
+	let mut stmt = con.prepare(&query.replacen("SELECT", "SELECT rowid,", 1)).unwrap();
+	let res = super::from_rows_with_rowid::<Numbers>(stmt.query([]).unwrap())
+		.with_options(options)
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, vec![(1, expected)]);
+
+	let res = con
+		.query_row("SELECT 'abc' AS f_integer, 1.0 AS f_real, 1 AS f_text", [], from_row)
+		.unwrap();
+	match res.unwrap_err() {
+		Error::Deserialization { column, message, .. } => {
+			assert_eq!(column.as_deref(), Some("f_integer"));
+			assert!(message.contains("\"abc\""), "{message}");
+		}
+		e => panic!("unexpected error: {e}"),
+	}
+}
+
+#[test]
+fn test_case_insensitive_columns() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct User {
+		id: i64,
+		#[serde(rename = "userName")]
+		user_name: String,
+		#[serde(default)]
+		age: Option<i64>,
+	}
+
+	let con = make_connection();
+	let query = "SELECT 1 AS ID, 'joe' AS USERNAME, 30 AS Age";
+	let expected = User {
+		id: 1,
+		user_name: "joe".into(),
+		age: Some(30),
+	};
+	// exact match by default
+	let res = con.query_row(query, [], |row| Ok(super::from_row::<User>(row))).unwrap();
+	assert!(res.is_err());
+
+	let ci = DeserializeOptions::default().case_insensitive_columns(true);
+	let mut stmt = con.prepare(query).unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_with_options::<User>(row, &columns, &ci)))
+		.unwrap();
+	assert_eq!(res.unwrap(), expected);
+	let res = super::from_rows::<User>(stmt.query([]).unwrap())
+		.with_options(ci)
+		.next()
+		.unwrap();
+	assert_eq!(res.unwrap(), expected);
+
+	// flatten makes serde deserialize the struct as a map, so the columns are matched case-sensitively
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Flattened {
+		#[serde(flatten)]
+		user: User,
+	}
+	let res = super::from_rows::<Flattened>(stmt.query([]).unwrap())
+		.with_options(ci)
+		.next()
+		.unwrap();
+	assert!(res.is_err());
+
+	// missing field detection is also case-insensitive
+	let options = DeserializeOptions::default()
+		.case_insensitive_columns(true)
+		.default_missing_fields(true);
+	let mut stmt = con.prepare("SELECT 2 AS Id, 'ann' AS UserName").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_with_options::<User>(row, &columns, &options)))
+		.unwrap();
+	assert_eq!(
+		res.unwrap(),
+		User {
+			id: 2,
+			user_name: "ann".into(),
+			age: None,
+		}
+	);
+}
+
+#[test]
+fn test_strict_numeric() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_real) VALUES(5, 2.5)", []).unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_real FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let options = DeserializeOptions::default().strict_numeric(true);
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Matching {
+		f_integer: i32,
+		f_real: f32,
+	}
+	let res = stmt
+		.query_row([], |row| {
+			Ok(Matching::deserialize(RowDeserializer::from_row_with_options(
+				row, &columns, options,
+			)))
+		})
+		.unwrap();
+	assert_eq!(
+		res.unwrap(),
+		Matching {
+			f_integer: 5,
+			f_real: 2.5
+		}
+	);
+
+	// INTEGER into float is coerced by default and rejected in strict mode
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct IntegerToFloat {
+		f_integer: f64,
+	}
+	let res = stmt.query_row([], |row| Ok(super::from_row::<IntegerToFloat>(row))).unwrap();
+	assert_eq!(res.unwrap(), IntegerToFloat { f_integer: 5. });
+	let res = stmt
+		.query_row([], |row| {
+			Ok(IntegerToFloat::deserialize(RowDeserializer::from_row_with_options(
+				row, &columns, options,
+			)))
+		})
+		.unwrap();
+	match res {
+		Err(Error::TypeMismatch {
+			column: Some(column),
+			expected: "REAL",
+			found: rusqlite::types::Type::Integer,
+			..
+		}) => assert_eq!(column, "f_integer"),
+		_ => panic!("Unexpected result: {:?}", res),
+	}
+
+	// REAL into integer is rejected in strict mode
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct FloatToInteger {
+		f_integer: i64,
+		f_real: u8,
+	}
+	let res = stmt
+		.query_row([], |row| {
+			Ok(FloatToInteger::deserialize(RowDeserializer::from_row_with_options(
+				row, &columns, options,
+			)))
+		})
+		.unwrap();
+	match res {
+		Err(Error::TypeMismatch {
+			column: Some(column),
+			expected: "INTEGER",
+			found: rusqlite::types::Type::Real,
+			..
+		}) => assert_eq!(column, "f_real"),
+		_ => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_column() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Point {
+		x: i64,
+		y: i64,
+	}
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_text) VALUES(1, ?)",
+		[r#"[{"x": 1, "y": 2}, {"x": 3, "y": 4}]"#],
+	)
+	.unwrap();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(2, ?)", [r#"{"x": 5, "y": 6}"#])
+		.unwrap();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(3, ?)", [r#"[{"x": 1,"#])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test ORDER BY f_integer").unwrap();
+	let mut rows = stmt.query([]).unwrap();
+
+	let row = rows.next().unwrap().unwrap();
+	assert_eq!(
+		super::from_json_array_column::<Point>(row, "f_text").unwrap(),
+		[Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+	);
+	assert!(matches!(
+		super::from_json_column::<Point>(row, 0),
+		Err(Error::TypeMismatch { expected: "TEXT", .. })
+	));
+
+	let row = rows.next().unwrap().unwrap();
+	assert_eq!(super::from_json_column::<Point>(row, 1).unwrap(), Point { x: 5, y: 6 });
+
+	let row = rows.next().unwrap().unwrap();
+	match super::from_json_array_column::<Point>(row, 1) {
+		Err(Error::Deserialization {
+			column: Some(column), ..
+		}) => assert_eq!(column, "f_text"),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_params_for_statement() {
+	#[derive(Serialize)]
+	struct Test {
+		f_text: &'static str,
+		f_unused: bool,
+		f_integer: i64,
+		f_real: f64,
+	}
+	let src = Test {
+		f_text: "text",
+		f_unused: true,
+		f_integer: 10,
+		f_real: 1.5,
+	};
+	let con = make_connection();
+	{
+		let mut stmt = con
+			.prepare("INSERT INTO test(f_integer, f_real, f_text) VALUES(:f_integer, @f_real, $f_text)")
+			.unwrap();
+		let params = super::to_params_for_statement(&stmt, &src).unwrap();
+		stmt.execute(params).unwrap();
+	}
+	let mut stmt = con.prepare("SELECT f_integer, f_real, f_text FROM test").unwrap();
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<(i64, f64, String)>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, (10, 1.5, "text".into()));
+
+	let stmt = con
+		.prepare("INSERT INTO test(f_integer, f_blob) VALUES(:f_integer, :f_blob)")
+		.unwrap();
+	assert!(matches!(
+		super::to_params_for_statement(&stmt, &src),
+		Err(Error::Serialization(_))
+	));
+	let stmt = con
+		.prepare("INSERT INTO test(f_integer, f_real) VALUES(:f_integer, ?)")
+		.unwrap();
+	assert!(matches!(
+		super::to_params_for_statement(&stmt, &src),
+		Err(Error::Serialization(_))
+	));
+}
+
+#[test]
+fn test_unexpected_null() {
+	fn assert_unexpected_null<D: serde::de::DeserializeOwned + Debug>(select: &str) {
+		let con = make_connection();
+		con.execute("INSERT INTO test(f_integer) VALUES(1)", []).unwrap();
+		let mut stmt = con.prepare(&format!("SELECT {} FROM test", select)).unwrap();
+		let res = stmt.query_row([], |row| Ok(super::from_row::<D>(row))).unwrap();
+		match res {
+			Err(Error::UnexpectedNull {
+				column: Some(column), ..
+			}) => assert_eq!(column, "f_null"),
+			_ => panic!("Unexpected result: {:?}", res),
+		}
+	}
+
+	#[derive(Deserialize, Debug)]
+	#[allow(dead_code)]
+	enum Enum {
+		A,
+	}
+	#[derive(Deserialize, Debug)]
+	#[allow(dead_code)]
+	struct Test<T> {
+		f_null: T,
+	}
+	assert_unexpected_null::<Test<i64>>("f_null");
+	assert_unexpected_null::<Test<u8>>("f_null");
+	assert_unexpected_null::<Test<bool>>("f_null");
+	assert_unexpected_null::<Test<String>>("f_null");
+	assert_unexpected_null::<Test<char>>("f_null");
+	assert_unexpected_null::<Test<serde_bytes::ByteBuf>>("f_null");
+	assert_unexpected_null::<Test<Enum>>("f_null");
+	assert_unexpected_null::<(i64, i64)>("f_integer, f_null");
+
+	// types that can represent NULL are not affected
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer) VALUES(1)", []).unwrap();
+	let mut stmt = con.prepare("SELECT f_null, f_null, f_null FROM test").unwrap();
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<(Option<i64>, f64, ())>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res.0, None);
+	assert!(res.1.is_nan());
+}
+
+#[test]
+fn test_heterogeneous_tuple() {
+	let src = (1, Some("x"), None::<i64>, vec![1_u8, 2], 2.5, ());
+	let con = rusqlite::Connection::open_in_memory().unwrap();
+	let mut stmt = con
+		.prepare("SELECT typeof(?), typeof(?), typeof(?), typeof(?), typeof(?), typeof(?)")
+		.unwrap();
+	let res = stmt
+		.query_row(super::to_params(&src).unwrap(), |row| {
+			Ok(super::from_row::<(String, String, String, String, String, String)>(row))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		(
+			"integer".into(),
+			"text".into(),
+			"null".into(),
+			"blob".into(),
+			"real".into(),
+			"null".into()
+		)
+	);
+	let mut stmt = con.prepare("SELECT ?, ?, ?, ?, ?, ?").unwrap();
+	let res = stmt
+		.query_row(super::to_params(&src).unwrap(), |row| {
+			Ok(super::from_row::<(i64, Option<String>, Option<i64>, Vec<u8>, f64, ())>(row))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, (1, Some("x".into()), None, vec![1, 2], 2.5, ()));
+}
+
+#[test]
+fn test_field_map() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Report {
+		amount: i64,
+		label: String,
+		f_real: f64,
+	}
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_real, f_text) VALUES(7, 1.5, 'seven')", [])
+		.unwrap();
+	let mut field_map = collections::HashMap::new();
+	field_map.insert("f_integer".to_string(), "amount".to_string());
+	field_map.insert("f_text".to_string(), "label".to_string());
+	let mut stmt = con.prepare("SELECT f_integer, f_real, f_text FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| {
+			Ok(super::from_row_with_field_map::<Report>(row, &columns, &field_map))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		Report {
+			amount: 7,
+			label: "seven".into(),
+			f_real: 1.5,
+		}
+	);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_fallback() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	enum Event {
+		Start,
+		Note(String),
+		Move { x: i64, y: i64 },
+		Resize(u32, u32),
+	}
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Record {
+		id: i64,
+		state: Event,
+		last: Event,
+		events: Vec<Event>,
+		empty: Vec<i64>,
+		#[serde(with = "serde_bytes")]
+		bytes: Vec<u8>,
+		labels: collections::BTreeMap<String, i64>,
+	}
+
+	let con = make_connection_with_spec(
+		"
+		id INT,
+		state TEXT CHECK(typeof(state) == 'text'),
+		last TEXT CHECK(typeof(last) == 'text'),
+		events TEXT CHECK(typeof(events) == 'text'),
+		empty TEXT CHECK(typeof(empty) == 'text'),
+		bytes BLOB CHECK(typeof(bytes) == 'blob'),
+		labels TEXT CHECK(typeof(labels) == 'text')
+	",
+	);
+	let src = Record {
+		id: 1,
+		state: Event::Move { x: 1, y: -2 },
+		last: Event::Start,
+		events: vec![Event::Start, Event::Note("note".into()), Event::Resize(3, 4)],
+		empty: vec![],
+		bytes: vec![1, 2, 3],
+		labels: [("a".to_string(), 1), ("b".to_string(), 2)].into_iter().collect(),
+	};
+	// not enabled by default
+	assert!(matches!(super::to_params_named(&src), Err(Error::Unsupported(_))));
+	let options = SerializeOptions::default().compound_as_json(true);
+	con.execute(
+		"INSERT INTO test VALUES(:id, :state, :last, :events, :empty, :bytes, :labels)",
+		super::to_params_named_with_options(&src, &options)
+			.unwrap()
+			.to_slice()
+			.as_slice(),
+	)
+	.unwrap();
+	let stored = con
+		.query_row("SELECT state, events FROM test", [], |row| {
+			Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+		})
+		.unwrap();
+	assert_eq!(stored.0, r#"{"Move":{"x":1,"y":-2}}"#);
+	assert_eq!(stored.1, r#"["Start",{"Note":"note"},{"Resize":[3,4]}]"#);
+
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = super::from_rows::<Record>(stmt.query([]).unwrap()).next().unwrap().unwrap();
+	assert_eq!(res, src);
+
+	// newtype variants keep their baseline representation by default
+	let to_value = |value: &Event, options: &SerializeOptions| {
+		let params = super::to_params_named_with_options(collections::BTreeMap::from([("v", value)]), options).unwrap();
+		crate::ser::param_to_value(params.to_slice()[0].1).unwrap()
+	};
+	assert_eq!(
+		to_value(&Event::Note("note".into()), &SerializeOptions::default()),
+		Value::Text("note".into())
+	);
+	assert_eq!(
+		to_value(&Event::Note("note".into()), &options),
+		Value::Text(r#"{"Note":"note"}"#.into())
+	);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_value_field() {
+	#[derive(Deserialize, Serialize, Debug, PartialEq)]
+	struct Doc {
+		id: i64,
+		body: serde_json::Value,
+		note: serde_json::Value,
+	}
+
+	let con = make_connection_with_spec("id INT, body TEXT CHECK(typeof(body) == 'text'), note TEXT");
+	let src = Doc {
+		id: 1,
+		body: serde_json::json!({"name": "doc", "tags": ["a", "b"], "meta": {"size": 3, "draft": false, "parent": null}}),
+		note: serde_json::json!("{not json"),
+	};
+	con.execute(
+		"INSERT INTO test VALUES(?, ?, ?)",
+		super::to_params_with_options(&src, &SerializeOptions::default().compound_as_json(true)).unwrap(),
+	)
+	.unwrap();
+	let stored = con
+		.query_row("SELECT body FROM test", [], |row| row.get::<_, String>(0))
+		.unwrap();
+	assert_eq!(stored, serde_json::to_string(&src.body).unwrap());
+
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let options = DeserializeOptions::default().parse_json_text(true);
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_with_options::<Doc>(row, &columns, &options)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, src);
+
+	// without the option JSON-looking strings stay strings
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Flat {
+		id: i64,
+		#[serde(flatten)]
+		rest: collections::HashMap<String, String>,
+	}
+	let res = con
+		.query_row("SELECT 1 AS id, '[1]' AS text", [], |row| Ok(super::from_row::<Flat>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res.rest["text"], "[1]");
+	let res = con
+		.query_row("SELECT '[1]' AS value", [], |row| {
+			Ok(super::from_row::<collections::HashMap<String, serde_json::Value>>(row))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(res["value"], serde_json::Value::String("[1]".into()));
+}
+
+#[test]
+fn test_rows_error_row_index() {
+	#[derive(Deserialize, Debug)]
+	struct Item {
+		#[allow(dead_code)]
+		f_integer: i64,
+	}
+
+	let con = make_connection_with_spec("f_integer");
+	con.execute("INSERT INTO test(f_integer) VALUES(1), (2), ('three'), (4)", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer FROM test").unwrap();
+	let res = super::from_rows::<Item>(stmt.query([]).unwrap()).collect::<Vec<_>>();
+	assert_eq!(res.len(), 4);
+	assert!(res[0].is_ok() && res[1].is_ok() && res[3].is_ok());
+	match &res[2] {
+		Err(e @ Error::Deserialization { column, row, .. }) => {
+			assert_eq!(column.as_deref(), Some("f_integer"));
+			assert_eq!(*row, Some(2));
+			assert!(
+				e.to_string()
+					.starts_with("Deserialization failed for column: f_integer in row: 2 error: "),
+				"{e}"
+			);
+		}
+		res => panic!("unexpected result: {res:?}"),
+	}
+
+	// skipped rows are counted too
+	{
+		let mut rows = stmt.query([]).unwrap();
+		let res = super::from_rows_ref::<Item>(&mut rows).nth(2).unwrap();
+		assert!(matches!(res, Err(Error::Deserialization { row: Some(2), .. })));
+	}
+	let res = super::from_rows_filtered::<Item, _>(stmt.query([]).unwrap(), |row| row.get_ref(0).unwrap().as_i64().is_err())
+		.collect::<Vec<_>>();
+	assert!(matches!(res[..], [Err(Error::Deserialization { row: Some(2), .. })]));
+
+	// single row functions don't know the index
+	let res = con
+		.query_row("SELECT 'x' AS f_integer", [], |row| Ok(super::from_row::<Item>(row)))
+		.unwrap();
+	assert!(matches!(res, Err(Error::Deserialization { row: None, .. })));
+
+	// other deserialization errors get the index too
+	let mut stmt = con
+		.prepare("SELECT 1 AS f_integer UNION ALL SELECT NULL UNION ALL SELECT 2.5 UNION ALL SELECT 3")
+		.unwrap();
+	let res = super::from_rows::<Item>(stmt.query([]).unwrap())
+		.with_options(DeserializeOptions::default().strict_numeric(true))
+		.collect::<Vec<_>>();
+	match &res[1] {
+		Err(e @ Error::UnexpectedNull { row: Some(1), .. }) => {
+			assert_eq!(e.to_string(), "Unexpected NULL value in column: f_integer in row: 1")
+		}
+		res => panic!("unexpected result: {res:?}"),
+	}
+	match &res[2] {
+		Err(e @ Error::TypeMismatch { row: Some(2), .. }) => {
+			assert_eq!(
+				e.to_string(),
+				"Type mismatch for column: f_integer in row: 2 expected: INTEGER found: Real"
+			)
+		}
+		res => panic!("unexpected result: {res:?}"),
+	}
+	let res = super::from_rows::<(i64, i64)>(stmt.query([]).unwrap()).next().unwrap();
+	assert!(matches!(res, Err(Error::ColumnCountMismatch { row: Some(0), .. })));
+}
+
+#[test]
+fn test_rows_vec() {
+	let con = make_connection();
+	for i in 0..3 {
+		con.execute("INSERT INTO test(f_integer) VALUES(?)", [i]).unwrap();
+	}
+	let mut stmt = con.prepare("SELECT f_integer FROM test ORDER BY f_integer LIMIT 10").unwrap();
+	let res = super::from_rows_vec::<(i64,)>(stmt.query([]).unwrap(), 10).unwrap();
+	assert_eq!(res, [(0,), (1,), (2,)]);
+	assert!(res.capacity() >= 10);
+
+	let res = super::from_rows_vec::<(i64,)>(stmt.query([]).unwrap(), 1).unwrap();
+	assert_eq!(res, [(0,), (1,), (2,)]);
+
+	let mut stmt = con.prepare("SELECT f_null FROM test").unwrap();
+	assert!(matches!(
+		super::from_rows_vec::<(i64,)>(stmt.query([]).unwrap(), 3),
+		Err(Error::UnexpectedNull { .. })
+	));
+
+	// stops at the first bad row
+	let mut stmt = con
+		.prepare("SELECT f_integer FROM test WHERE f_integer = 0 UNION ALL SELECT 'x' UNION ALL SELECT 'y'")
+		.unwrap();
+	assert!(matches!(
+		super::from_rows_vec::<(i64,)>(stmt.query([]).unwrap(), 3),
+		Err(Error::Deserialization { row: Some(1), .. })
+	));
+}
+
+#[test]
+fn test_unit_struct_case_insensitive() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Marker;
+
+	let con = make_connection();
+	let lenient = DeserializeOptions::default().case_insensitive_unit_struct(true);
+	let deserialize = |text: &str, options: DeserializeOptions| {
+		con.query_row("SELECT ?", [text], |row| {
+			Ok(Marker::deserialize(RowDeserializer::from_row_with_options(row, &[], options)))
+		})
+		.unwrap()
+	};
+	assert_eq!(deserialize("Marker", DeserializeOptions::default()).unwrap(), Marker);
+	assert_eq!(deserialize("Marker", lenient).unwrap(), Marker);
+	assert!(deserialize("MARKER", DeserializeOptions::default()).is_err());
+	assert_eq!(deserialize("MARKER", lenient).unwrap(), Marker);
+	assert_eq!(deserialize("marker", lenient).unwrap(), Marker);
+	assert!(deserialize("Other", DeserializeOptions::default()).is_err());
+	assert!(deserialize("Other", lenient).is_err());
+}
+
+#[test]
+fn test_peek_storage_class() {
+	#[derive(Debug, PartialEq)]
+	enum Id {
+		Number(i64),
+		Name(String),
+	}
+
+	impl Id {
+		fn deserialize(de: RowDeserializer) -> Result<Self, Error> {
+			match de.peek_storage_class()? {
+				rusqlite::types::Type::Integer => i64::deserialize(de).map(Id::Number),
+				_ => String::deserialize(de).map(Id::Name),
+			}
+		}
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, NULL), (NULL, 'abc')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT coalesce(f_integer, f_text) FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_map([], |row| {
+			let de = RowDeserializer::from_row_with_columns(row, &columns);
+			Ok(Id::deserialize(de))
+		})
+		.unwrap()
+		.collect::<Result<Result<Vec<_>, _>, _>>()
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, [Id::Number(1), Id::Name("abc".into())]);
+}
+
+#[test]
+fn test_skip_serializing_id() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		#[serde(skip_serializing)]
+		id: i64,
+		f_integer: i64,
+		f_text: String,
+	}
+	let con = make_connection_with_spec(
+		"
+		id INTEGER PRIMARY KEY AUTOINCREMENT,
+		f_integer INT,
+		f_text TEXT
+	",
+	);
+	let src = Test {
+		id: 100,
+		f_integer: 10,
+		f_text: "first".into(),
+	};
+	let params = super::to_params_named(&src).unwrap();
+	assert_eq!(
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+		[":f_integer", ":f_text"]
+	);
+	// the reservation for the struct fields accounts for the skipped field
+	assert_eq!(params.capacity(), params.len());
+	for _ in 0..2 {
+		con.execute(
+			"INSERT INTO test(f_integer, f_text) VALUES(:f_integer, :f_text)",
+			params.to_slice().as_slice(),
+		)
+		.unwrap();
+	}
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = super::from_rows::<Test>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [1, 2].map(|id| Test { id, ..src.clone() }));
+}
+
+#[test]
+fn test_blob_records() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Position(i16, i16);
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Record {
+		id: u32,
+		active: bool,
+		position: Position,
+		value: f64,
+		tag: [u8; 2],
+	}
+
+	let mut blob = vec![];
+	for (id, active, x, y, value, tag) in [
+		(1u32, true, -1i16, 2i16, 0.5f64, *b"ab"),
+		(2, false, 300, -300, -1e10, *b"cd"),
+	] {
+		blob.extend_from_slice(&id.to_le_bytes());
+		blob.push(u8::from(active));
+		blob.extend_from_slice(&x.to_le_bytes());
+		blob.extend_from_slice(&y.to_le_bytes());
+		blob.extend_from_slice(&value.to_le_bytes());
+		blob.extend_from_slice(&tag);
+	}
+	assert_eq!(blob.len(), 2 * 19);
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_blob, f_integer) VALUES(?, 1)", [&blob])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_blob, f_integer FROM test").unwrap();
+	let mut rows = stmt.query([]).unwrap();
+	let row = rows.next().unwrap().unwrap();
+	assert_eq!(
+		super::blob_records::<Record>(row, "f_blob", 19).unwrap(),
+		[
+			Record {
+				id: 1,
+				active: true,
+				position: Position(-1, 2),
+				value: 0.5,
+				tag: *b"ab",
+			},
+			Record {
+				id: 2,
+				active: false,
+				position: Position(300, -300),
+				value: -1e10,
+				tag: *b"cd",
+			},
+		]
+	);
+	// padding at the end of the record is skipped
+	assert_eq!(
+		super::blob_records::<(u32, bool)>(row, 0, 19).unwrap(),
+		[(1, true), (2, false)]
+	);
+	// records don't fit
+	match super::blob_records::<Record>(row, 0, 17) {
+		Err(Error::Deserialization {
+			column: Some(column), ..
+		}) => assert_eq!(column, "f_blob"),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	assert!(matches!(
+		super::blob_records::<(u64, u64, u64)>(row, 0, 19),
+		Err(Error::Deserialization { .. })
+	));
+	assert!(matches!(
+		super::blob_records::<Record>(row, 1, 19),
+		Err(Error::TypeMismatch { expected: "BLOB", .. })
+	));
+}
+
+#[test]
+fn test_params_empty() {
+	let error_on_empty = SerializeOptions::default().error_on_empty(true);
+
+	let res = Vec::<i64>::new().serialize(PositionalSliceSerializer::default()).unwrap();
+	assert!(res.is_empty());
+	assert!(matches!(
+		Vec::<i64>::new().serialize(PositionalSliceSerializer::with_options(error_on_empty)),
+		Err(Error::Serialization(_))
+	));
+	assert!(matches!(
+		[0; 0].serialize(PositionalSliceSerializer::with_options(error_on_empty)),
+		Err(Error::Serialization(_))
+	));
+
+	// unit produces a single NULL argument so it's not affected by the option
+	for options in [SerializeOptions::default(), error_on_empty] {
+		let res = ().serialize(PositionalSliceSerializer::with_options(options)).unwrap();
+		assert_eq!(res.len(), 1);
+		assert_eq!(res[0].to_sql().unwrap(), ToSqlOutput::Borrowed(ValueRef::Null));
+	}
+
+	let res = vec![1]
+		.serialize(PositionalSliceSerializer::with_options(error_on_empty))
+		.unwrap();
+	assert_eq!(res.len(), 1);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_row_to_json() {
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real, f_text, f_blob) VALUES(-5, 1.25, 'text', x'00ff10')",
+		[],
+	)
+	.unwrap();
+	let mut stmt = con
+		.prepare("SELECT f_integer, f_real, f_text, f_blob, f_null FROM test")
+		.unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| Ok(super::row_to_json(row, &columns)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		serde_json::json!({
+			"f_integer": -5,
+			"f_real": 1.25,
+			"f_text": "text",
+			"f_blob": "AP8Q",
+			"f_null": null,
+		})
+	);
+	assert_eq!(
+		res.to_string(),
+		r#"{"f_blob":"AP8Q","f_integer":-5,"f_null":null,"f_real":1.25,"f_text":"text"}"#
+	);
+}
+
+#[cfg(feature = "net")]
+#[test]
+fn test_net() {
+	use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		#[serde(with = "crate::net::ipv4_addr")]
+		v4: Ipv4Addr,
+		#[serde(with = "crate::net::ipv6_addr")]
+		v6: Ipv6Addr,
+		#[serde(with = "crate::net::ip_addr")]
+		any: IpAddr,
+	}
+	let con = make_connection_with_spec(
+		"
+		v4 BLOB CHECK(typeof(v4) == 'blob' AND length(v4) == 4),
+		v6 BLOB CHECK(typeof(v6) == 'blob' AND length(v6) == 16),
+		any BLOB CHECK(typeof(any) == 'blob')
+	",
+	);
+	let src = [
+		Test {
+			v4: Ipv4Addr::new(192, 168, 0, 1),
+			v6: "2001:db8::ff00:42:8329".parse().unwrap(),
+			any: IpAddr::V4(Ipv4Addr::LOCALHOST),
+		},
+		Test {
+			v4: Ipv4Addr::BROADCAST,
+			v6: Ipv6Addr::LOCALHOST,
+			any: IpAddr::V6("::ffff:1.2.3.4".parse().unwrap()),
+		},
+	];
+	for row in &src {
+		con.execute(
+			"INSERT INTO test VALUES(:v4, :v6, :any)",
+			super::to_params_named(row).unwrap().to_slice().as_slice(),
+		)
+		.unwrap();
+	}
+	let blob = con
+		.query_row("SELECT v4 FROM test LIMIT 1", [], |row| row.get::<_, Vec<u8>>(0))
+		.unwrap();
+	assert_eq!(blob, [192, 168, 0, 1]);
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = super::from_rows::<Test>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, src);
+
+	// wrong length
+	let mut stmt = con.prepare("SELECT v6 AS v4, v4 AS v6, x'0102' AS any FROM test").unwrap();
+	let res = super::from_rows::<Test>(stmt.query([]).unwrap()).next().unwrap();
+	match res {
+		Err(Error::Deserialization {
+			column: Some(column), ..
+		}) => assert_eq!(column, "v4"),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	let mut stmt = con.prepare("SELECT v4, v6, x'0102' AS any FROM test").unwrap();
+	let res = super::from_rows::<Test>(stmt.query([]).unwrap()).next().unwrap();
+	match res {
+		Err(e @ Error::Deserialization { .. }) => assert_eq!(
+			e.to_string(),
+			"Deserialization failed for column: any in row: 0 error: invalid length 2, expected BLOB of 4 or 16 bytes for IpAddr"
+		),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_empty_enum() {
+	#[derive(Deserialize, Serialize, Debug, PartialEq)]
+	enum Status {
+		Active,
+		Closed,
+	}
+	#[derive(Deserialize, Serialize, Debug, PartialEq)]
+	struct Lenient {
+		#[serde(with = "crate::empty_enum")]
+		f_text: Option<Status>,
+		f_comment: Option<String>,
+	}
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Strict {
+		f_text: Option<Status>,
+	}
+
+	let con = make_connection_with_spec("f_integer INT, f_text TEXT, f_comment TEXT");
+	con.execute(
+		"INSERT INTO test(f_integer, f_text, f_comment) VALUES(1, NULL, ''), (2, '', ''), (3, 'Closed', '')",
+		[],
+	)
+	.unwrap();
+	let mut stmt = con.prepare("SELECT f_text, f_comment FROM test ORDER BY f_integer").unwrap();
+
+	let res = super::from_rows::<Strict>(stmt.query([]).unwrap()).collect::<Vec<_>>();
+	assert!(matches!(res[0], Ok(Strict { f_text: None })));
+	assert!(matches!(res[1], Err(Error::Deserialization { .. })));
+	assert!(matches!(
+		res[2],
+		Ok(Strict {
+			f_text: Some(Status::Closed)
+		})
+	));
+
+	// only the enum field is affected, Option<String> still reads '' as Some("")
+	let res = super::from_rows::<Lenient>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	let expected = [None, None, Some(Status::Closed)]
+		.into_iter()
+		.map(|f_text| Lenient {
+			f_text,
+			f_comment: Some(String::new()),
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(res, expected);
+
+	// None is stored as ''
+	let params = super::to_params_named(&expected[0]).unwrap();
+	let stored: String = con
+		.query_row("SELECT :f_text, :f_comment", params.to_slice().as_slice(), |row| row.get(0))
+		.unwrap();
+	assert_eq!(stored, "");
+	let row = [
+		("f_text".to_owned(), Value::Text("Other".into())),
+		("f_comment".to_owned(), Value::Null),
+	];
+	assert!(super::from_value_row::<Lenient>(&row).is_err());
+}
+
+#[test]
+fn test_named_values_filtered() {
+	#[derive(Serialize)]
+	struct Test {
+		id: i64,
+		name: Option<String>,
+		score: Option<f64>,
+		data: serde_bytes::ByteBuf,
+	}
+	let src = Test {
+		id: 5,
+		name: None,
+		score: Some(1.5),
+		data: serde_bytes::ByteBuf::from(vec![1, 2]),
+	};
+	let res = super::to_named_values_filtered(&src, |_, value| *value != Value::Null).unwrap();
+	assert_eq!(
+		res,
+		[
+			("id".to_string(), Value::Integer(5)),
+			("score".to_string(), Value::Real(1.5)),
+			("data".to_string(), Value::Blob(vec![1, 2])),
+		]
+	);
+
+	let res = super::to_named_values_filtered(&src, |name, _| name == "name").unwrap();
+	assert_eq!(res, [("name".to_string(), Value::Null)]);
+}
+
+#[test]
+fn test_blob_representation() {
+	#[derive(Debug, PartialEq)]
+	enum Dynamic {
+		Integer(i64),
+		Text(String),
+		Bytes(Vec<u8>),
+		Seq(Vec<u8>),
+	}
+
+	impl<'de> serde::Deserialize<'de> for Dynamic {
+		fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			struct DynamicVisitor;
+
+			impl<'de> serde::de::Visitor<'de> for DynamicVisitor {
+				type Value = Dynamic;
+
+				fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+					formatter.write_str("any value")
+				}
+
+				fn visit_i64<E>(self, v: i64) -> Result<Dynamic, E> {
+					Ok(Dynamic::Integer(v))
+				}
+
+				fn visit_str<E>(self, v: &str) -> Result<Dynamic, E> {
+					Ok(Dynamic::Text(v.to_owned()))
+				}
+
+				fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Dynamic, E> {
+					Ok(Dynamic::Bytes(v))
+				}
+
+				fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Dynamic, A::Error> {
+					let mut out = vec![];
+					while let Some(byte) = seq.next_element()? {
+						out.push(byte);
+					}
+					Ok(Dynamic::Seq(out))
+				}
+			}
+
+			deserializer.deserialize_any(DynamicVisitor)
+		}
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text, f_blob) VALUES(1, 'text', x'0102')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text, f_blob FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let deserialize = |stmt: &mut rusqlite::Statement, blob_representation| {
+		stmt
+			.query_row([], |row| {
+				Ok(collections::HashMap::<String, Dynamic>::deserialize(
+					RowDeserializer::from_row_with_options(
+						row,
+						&columns,
+						DeserializeOptions::default().blob_representation(blob_representation),
+					),
+				))
+			})
+			.unwrap()
+			.unwrap()
+	};
+
+	let res = deserialize(&mut stmt, BlobRepresentation::Seq);
+	assert_eq!(res["f_integer"], Dynamic::Integer(1));
+	assert_eq!(res["f_text"], Dynamic::Text("text".into()));
+	assert_eq!(res["f_blob"], Dynamic::Seq(vec![1, 2]));
+
+	let res = deserialize(&mut stmt, BlobRepresentation::Bytes);
+	assert_eq!(res["f_integer"], Dynamic::Integer(1));
+	assert_eq!(res["f_text"], Dynamic::Text("text".into()));
+	assert_eq!(res["f_blob"], Dynamic::Bytes(vec![1, 2]));
+
+	// types that ask for bytes explicitly are not affected
+	let res = stmt
+		.query_row([], |row| {
+			Ok(<(i64, String, Vec<u8>)>::deserialize(RowDeserializer::from_row_with_options(
+				row,
+				&columns,
+				DeserializeOptions::default().blob_representation(BlobRepresentation::Bytes),
+			)))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, (1, "text".to_string(), vec![1, 2]));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_blob_representation_base64() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_blob) VALUES(1, x'0102ff')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_blob FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let deserialize = |stmt: &mut rusqlite::Statement, blob_representation| {
+		stmt
+			.query_row([], |row| {
+				Ok(collections::HashMap::<String, serde_json::Value>::deserialize(
+					RowDeserializer::from_row_with_options(
+						row,
+						&columns,
+						DeserializeOptions::default().blob_representation(blob_representation),
+					),
+				))
+			})
+			.unwrap()
+	};
+
+	let res = deserialize(&mut stmt, BlobRepresentation::Base64).unwrap();
+	assert_eq!(res["f_integer"], serde_json::json!(1));
+	assert_eq!(res["f_blob"], serde_json::json!("AQL/"));
+	let res = deserialize(&mut stmt, BlobRepresentation::Seq).unwrap();
+	assert_eq!(res["f_blob"], serde_json::json!([1, 2, 255]));
+	// serde_json::Value doesn't support bytes
+	assert!(deserialize(&mut stmt, BlobRepresentation::Bytes).is_err());
+	// the string is the same as the one produced by row_to_json()
+	let json = stmt
+		.query_row([], |row| Ok(super::row_to_json(row, &columns).unwrap()))
+		.unwrap();
+	assert_eq!(json["f_blob"], serde_json::json!("AQL/"));
+}
+
+#[test]
+fn test_map_of_blobs() {
+	let con = make_connection_with_spec(
+		"
+		first BLOB CHECK(typeof(first) == 'blob'),
+		second BLOB CHECK(typeof(second) == 'blob')
+	",
+	);
+	let mut src = collections::HashMap::new();
+	src.insert("first".to_string(), vec![1_u8, 2, 3]);
+	src.insert("second".to_string(), vec![]);
+	con.execute(
+		"INSERT INTO test VALUES(:first, :second)",
+		super::to_params_named(&src).unwrap().to_slice().as_slice(),
+	)
+	.unwrap();
+
+	// `ByteBuf` values skip the per-element serialization of `Vec<u8>`
+	let mut src_buf = collections::HashMap::new();
+	src_buf.insert("first".to_string(), serde_bytes::ByteBuf::from(vec![4, 5]));
+	src_buf.insert("second".to_string(), serde_bytes::ByteBuf::from(vec![6]));
+	con.execute(
+		"INSERT INTO test VALUES(:first, :second)",
+		super::to_params_named(&src_buf).unwrap().to_slice().as_slice(),
+	)
+	.unwrap();
+
+	let mut stmt = con.prepare("SELECT first, second FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_and_then([], |row| {
+			super::from_row_with_columns::<collections::HashMap<String, Vec<u8>>>(row, &columns)
+		})
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res[0], src);
+	assert_eq!(res[1]["first"], [4, 5]);
+	assert_eq!(res[1]["second"], [6]);
+}
+
+#[test]
+fn test_integer_from_real() {
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real) VALUES(1, 42), (2, -9007199254740991), (3, 9007199254740993), (4, 1.5)",
+		[],
+	)
+	.unwrap();
+	let mut stmt = con.prepare("SELECT f_real FROM test ORDER BY f_integer").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let deserialize = |stmt: &mut rusqlite::Statement, options| {
+		stmt
+			.query_map([], |row| {
+				Ok(<(i64,)>::deserialize(RowDeserializer::from_row_with_options(row, &columns, options)).map(|(val,)| val))
+			})
+			.unwrap()
+			.map(|res| res.unwrap())
+			.collect::<Vec<_>>()
+	};
+
+	// rejected by default
+	let res = deserialize(&mut stmt, DeserializeOptions::default());
+	assert!(res.iter().all(Result::is_err));
+
+	let res = deserialize(&mut stmt, DeserializeOptions::default().integer_from_real(true));
+	assert_eq!(res[0].as_ref().unwrap(), &42);
+	assert_eq!(res[1].as_ref().unwrap(), &-9007199254740991);
+	match &res[2] {
+		Err(e @ Error::Deserialization { .. }) => assert_eq!(
+			e.to_string(),
+			"Deserialization failed for column: f_real error: REAL value 9007199254740992 can't be exactly represented as an integer"
+		),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	assert!(matches!(res[3], Err(Error::Deserialization { .. })));
+
+	// converted value goes through the usual range check of the target type
+	let res = stmt
+		.query_row([], |row| {
+			Ok(<(u8,)>::deserialize(RowDeserializer::from_row_with_options(
+				row,
+				&columns,
+				DeserializeOptions::default().integer_from_real(true),
+			)))
+		})
+		.unwrap();
+	assert_eq!(res.unwrap(), (42,));
+}
+
+#[test]
+fn test_from_value_row() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		id: i64,
+		name: String,
+		score: f64,
+		data: serde_bytes::ByteBuf,
+		note: Option<String>,
+		active: bool,
+	}
+	let values = vec![
+		("id".to_string(), Value::Integer(7)),
+		("name".to_string(), Value::Text("seven".into())),
+		("score".to_string(), Value::Integer(3)),
+		("data".to_string(), Value::Blob(vec![1, 2])),
+		("note".to_string(), Value::Null),
+		("active".to_string(), Value::Integer(1)),
+	];
+	assert_eq!(
+		super::from_value_row::<Test>(&values).unwrap(),
+		Test {
+			id: 7,
+			name: "seven".into(),
+			score: 3.,
+			data: serde_bytes::ByteBuf::from(vec![1, 2]),
+			note: None,
+			active: true,
+		}
+	);
+	assert_eq!(
+		super::from_value_row::<(i64, String, f64)>(&values[..3]).unwrap(),
+		(7, "seven".to_string(), 3.)
+	);
+	assert_eq!(
+		super::from_value_row::<collections::BTreeMap<String, Option<i64>>>(&[
+			("a".to_string(), Value::Integer(1)),
+			("b".to_string(), Value::Null),
+		])
+		.unwrap(),
+		[("a".to_string(), Some(1)), ("b".to_string(), None)].into_iter().collect()
+	);
+
+	// errors are reported the same way as for the database rows
+	match super::from_value_row::<(serde_bytes::ByteBuf,)>(&values[..1]) {
+		Err(Error::Rusqlite(rusqlite::Error::InvalidColumnType(0, column, rusqlite::types::Type::Integer))) => {
+			assert_eq!(column, "id")
+		}
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	assert!(matches!(
+		super::from_value_row::<(i64, Option<i64>, i64)>(&values[4..]),
+		Err(Error::UnexpectedNull { .. })
+	));
+}
+
+#[test]
+fn test_value_row_round_trip() {
+	#[derive(Deserialize, Serialize, Debug, PartialEq)]
+	struct Test {
+		id: i64,
+		name: String,
+		score: f64,
+		#[serde(with = "serde_bytes")]
+		data: Vec<u8>,
+		note: Option<String>,
+		active: bool,
+	}
+	let src = Test {
+		id: 7,
+		name: "seven".into(),
+		score: 3.5,
+		data: vec![1, 2],
+		note: None,
+		active: true,
+	};
+	let values = super::to_value_row(&src).unwrap();
+	assert_eq!(
+		values,
+		[
+			("id".to_string(), Value::Integer(7)),
+			("name".to_string(), Value::Text("seven".into())),
+			("score".to_string(), Value::Real(3.5)),
+			("data".to_string(), Value::Blob(vec![1, 2])),
+			("note".to_string(), Value::Null),
+			("active".to_string(), Value::Integer(1)),
+		]
+	);
+	assert_eq!(super::from_value_row::<Test>(&values).unwrap(), src);
+	// positional values of a struct are the same values in the field order
+	assert_eq!(
+		super::to_value_vec(&src).unwrap(),
+		values.into_iter().map(|(_, value)| value).collect::<Vec<_>>()
+	);
+
+	let values = super::to_value_vec((1, "two", 3.5, None::<i64>)).unwrap();
+	assert_eq!(
+		values,
+		[Value::Integer(1), Value::Text("two".into()), Value::Real(3.5), Value::Null]
+	);
+	assert_eq!(super::to_value_vec(5).unwrap(), [Value::Integer(5)]);
+}
+
+#[test]
+fn test_first_char() {
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_text) VALUES(1, 'Ä'), (2, 'Ärger'), (3, '')",
+		[],
+	)
+	.unwrap();
+	let mut stmt = con.prepare("SELECT f_text FROM test ORDER BY f_integer").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let deserialize = |stmt: &mut rusqlite::Statement, options| {
+		stmt
+			.query_map([], |row| {
+				Ok(<(char,)>::deserialize(RowDeserializer::from_row_with_options(row, &columns, options)).map(|(val,)| val))
+			})
+			.unwrap()
+			.map(|res| res.unwrap())
+			.collect::<Vec<_>>()
+	};
+
+	let res = deserialize(&mut stmt, DeserializeOptions::default());
+	assert_eq!(res[0].as_ref().unwrap(), &'Ä');
+	assert!(matches!(res[1], Err(Error::Deserialization { .. })));
+	assert!(matches!(res[2], Err(Error::Deserialization { .. })));
+
+	let res = deserialize(&mut stmt, DeserializeOptions::default().first_char(true));
+	assert_eq!(res[0].as_ref().unwrap(), &'Ä');
+	assert_eq!(res[1].as_ref().unwrap(), &'Ä');
+	assert!(matches!(res[2], Err(Error::Deserialization { .. })));
+}
+
+#[test]
+fn test_from_row_by_index() {
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_real) VALUES(10, 20)", []).unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_integer, f_integer + 1 FROM test").unwrap();
+	let res = stmt
+		.query_row([], |row| {
+			Ok(super::from_row_by_index::<collections::BTreeMap<usize, i64>>(row))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(res, [(0, 10), (1, 10), (2, 11)].into_iter().collect());
+
+	// duplicated names collapse into a single entry when names are used
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<collections::BTreeMap<String, i64>>(row)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(res.len(), 2);
+
+	let mut stmt = con.prepare("SELECT f_integer, f_real FROM test").unwrap();
+	match stmt
+		.query_row([], |row| Ok(super::from_row_by_index::<collections::BTreeMap<u8, i64>>(row)))
+		.unwrap()
+	{
+		Err(Error::Deserialization {
+			column: Some(column), ..
+		}) => assert_eq!(column, "f_real"),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_params_named_with_fields_skip_none() {
+	#[derive(Serialize)]
+	struct Update {
+		id: i64,
+		name: Option<String>,
+		score: Option<f64>,
+		note: Option<String>,
+	}
+	let src = Update {
+		id: 1,
+		name: None,
+		score: Some(2.5),
+		note: Some("not listed".into()),
+	};
+	let params = super::to_params_named_with_fields_skip_none(&src, &["name", "score"]).unwrap();
+	assert_eq!(params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), [":score"]);
+	assert_eq!(params[0].1.to_sql().unwrap(), ToSqlOutput::Owned(Value::Real(2.5)));
+
+	let params = to_params_named_with_fields(&src, &["name", "score"]).unwrap();
+	assert_eq!(
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+		[":name", ":score"]
+	);
+}
+
+#[test]
+fn test_params_named_map_skip_none() {
+	let src = [("a", Some(1)), ("b", None), ("c", Some(3)), ("d", None)]
+		.into_iter()
+		.collect::<collections::BTreeMap<_, Option<i64>>>();
+	let params = src
+		.serialize(super::NamedSliceSerializer::with_options(
+			SerializeOptions::default().skip_none(true),
+		))
+		.unwrap();
+	assert_eq!(params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), [":a", ":c"]);
+	assert_eq!(params[1].1.to_sql().unwrap(), ToSqlOutput::Owned(Value::Integer(3)));
+
+	// NULL entries are bound by default
+	let params = super::to_params_named(&src).unwrap();
+	assert_eq!(params.len(), 4);
+}
+
+#[test]
+fn test_raw_value() {
+	use crate::ser::{Raw, RAW_VALUE_NEWTYPE};
+
+	#[derive(Serialize)]
+	struct Test {
+		f_integer: i64,
+		f_blob: Raw,
+		f_text: Raw,
+	}
+	let con = make_connection();
+	let src = Test {
+		f_integer: 1,
+		f_blob: Raw(Value::Blob(vec![0, 1, 2])),
+		f_text: Raw(Value::Text("text".into())),
+	};
+	con.execute(
+		"INSERT INTO test(f_integer, f_blob, f_text) VALUES(:f_integer, :f_blob, :f_text)",
+		super::to_params_named(&src).unwrap().to_slice().as_slice(),
+	)
+	.unwrap();
+	con.execute(
+		"INSERT INTO test(f_integer, f_blob, f_real) VALUES(?, ?, ?)",
+		super::to_params((2, Raw(Value::Blob(vec![3])), Raw(Value::Real(0.5)))).unwrap(),
+	)
+	.unwrap();
+	let mut stmt = con
+		.prepare("SELECT f_blob, f_text, f_real FROM test ORDER BY f_integer")
+		.unwrap();
+	let res = super::from_rows::<(Vec<u8>, Option<String>, Option<f64>)>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(
+		res,
+		[(vec![0, 1, 2], Some("text".to_string()), None), (vec![3], None, Some(0.5))]
+	);
+
+	assert_eq!(super::to_value_vec(Raw(Value::Null)).unwrap(), [Value::Null]);
+
+	// invalid payload of the marker
+	struct Invalid;
+	impl serde::Serialize for Invalid {
+		fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.serialize_newtype_struct(RAW_VALUE_NEWTYPE, &5)
+		}
+	}
+	test_ser_err(&Invalid, |err| matches!(*err, Error::Serialization(..)));
+}
+
+#[test]
+fn test_byte_array_from_blob() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Hashed {
+		id: i64,
+		hash: [u8; 4],
+	}
+
+	let con = make_connection();
+	let res = con
+		.query_row("SELECT 1 AS id, x'deadbeef' AS hash", [], |row| {
+			Ok(super::from_row::<Hashed>(row))
+		})
+		.unwrap();
+	assert_eq!(
+		res.unwrap(),
+		Hashed {
+			id: 1,
+			hash: [0xde, 0xad, 0xbe, 0xef]
+		}
+	);
+
+	// also with bytes representation
+	let mut stmt = con.prepare("SELECT 2 AS id, x'01020304' AS hash").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let options = DeserializeOptions::default().blob_representation(BlobRepresentation::Bytes);
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_with_options::<Hashed>(row, &columns, &options)))
+		.unwrap();
+	assert_eq!(res.unwrap().hash, [1, 2, 3, 4]);
+
+	for blob in ["x'0102'", "x'0102030405'"] {
+		let res = con
+			.query_row(&format!("SELECT 1 AS id, {blob} AS hash"), [], |row| {
+				Ok(super::from_row::<Hashed>(row))
+			})
+			.unwrap();
+		match res.unwrap_err() {
+			Error::Deserialization { column, message, .. } => {
+				assert_eq!(column.as_deref(), Some("hash"));
+				assert!(message.contains("expected length of 4 bytes"), "{message}");
+			}
+			e => panic!("unexpected error: {e}"),
+		}
+	}
+}
+
+#[test]
+fn test_byte_array_round_trip() {
+	#[derive(Deserialize, Serialize, Debug, PartialEq)]
+	struct Hashed {
+		id: i64,
+		hash: [u8; 32],
+	}
+
+	let con = make_connection_with_spec("id INT, hash BLOB CHECK(typeof(hash) == 'blob')");
+	let src = Hashed {
+		id: 1,
+		hash: core::array::from_fn(|i| i as u8 * 8),
+	};
+	con.execute(
+		"INSERT INTO test VALUES(:id, :hash)",
+		super::to_params_named(&src).unwrap().to_slice().as_slice(),
+	)
+	.unwrap();
+	con.execute("INSERT INTO test VALUES(?, ?)", super::to_params(&src).unwrap())
+		.unwrap();
+	let stored = con
+		.query_row("SELECT hash FROM test", [], |row| row.get::<_, Vec<u8>>(0))
+		.unwrap();
+	assert_eq!(stored, src.hash);
+
+	let mut stmt = con.prepare("SELECT * FROM test").unwrap();
+	let res = super::from_rows::<Hashed>(stmt.query([]).unwrap())
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [&src, &src].map(|h| Hashed { id: h.id, hash: h.hash }));
+
+	// tuples of anything but u8 can't go into a BLOB
+	#[derive(Serialize)]
+	struct Pair {
+		pair: (i64, i64),
+	}
+	assert!(super::to_params_named(Pair { pair: (1, 2) }).is_err());
+	#[cfg(feature = "serde_json")]
+	assert_eq!(
+		super::to_params_named_with_options(Pair { pair: (1, 2) }, &SerializeOptions::default().compound_as_json(true))
+			.unwrap()
+			.to_slice()[0]
+			.1
+			.to_sql()
+			.unwrap(),
+		rusqlite::types::ToSqlOutput::from("[1,2]")
+	);
+}
+
+#[test]
+fn test_max_blob_len() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Blobs {
+		seq: Vec<u8>,
+		#[serde(with = "serde_bytes")]
+		buf: Vec<u8>,
+	}
+
+	let con = make_connection();
+	let mut stmt = con.prepare("SELECT zeroblob(16) AS seq, zeroblob(16) AS buf UNION ALL SELECT zeroblob(17), zeroblob(4) UNION ALL SELECT zeroblob(4), zeroblob(17)").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let options = DeserializeOptions::default().max_blob_len(Some(16));
+	let res = stmt
+		.query_map([], |row| {
+			Ok(Blobs::deserialize(RowDeserializer::from_row_with_options(
+				row, &columns, options,
+			)))
+		})
+		.unwrap()
+		.map(|res| res.unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		res[0].as_ref().unwrap(),
+		&Blobs {
+			seq: vec![0; 16],
+			buf: vec![0; 16]
+		}
+	);
+	for (res, column) in res[1..].iter().zip(["seq", "buf"]) {
+		match res {
+			Err(Error::Deserialization {
+				column: Some(col),
+				message,
+				..
+			}) => {
+				assert_eq!(col, column);
+				assert!(message.starts_with("blob exceeds max length"), "{}", message);
+			}
+			res => panic!("Unexpected result: {:?}", res),
+		}
+	}
+}
+
+#[test]
+fn test_deser_rows_into_rows() {
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'one'), (2, 'two'), (3, 'three'), (4, 'four')")
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test ORDER BY f_integer").unwrap();
+	let mut rows = super::from_rows::<(i64, String)>(stmt.query([]).unwrap());
+	assert_eq!(rows.next().unwrap().unwrap(), (1, "one".into()));
+	let raw: i64 = rows.rows_mut().next().unwrap().unwrap().get(0).unwrap();
+	assert_eq!(raw, 2);
+	assert_eq!(rows.next().unwrap().unwrap(), (3, "three".into()));
+	let mut raw_rows = rows.into_rows();
+	let raw: String = raw_rows.next().unwrap().unwrap().get(1).unwrap();
+	assert_eq!(raw, "four");
+	let mut rows = super::from_rows::<(i64, String)>(raw_rows);
+	assert!(rows.next().is_none());
+}
+
+#[test]
+fn test_validate_struct_against_table() {
+	#[derive(Serialize, Default)]
+	struct Matching {
+		id: i64,
+		#[serde(rename = "title")]
+		name: String,
+		#[serde(skip)]
+		_cache: Vec<u8>,
+	}
+	#[derive(Serialize, Default)]
+	struct Drifted {
+		id: i64,
+		name: String,
+	}
+
+	let con = make_connection_with_spec("id INTEGER PRIMARY KEY, title TEXT");
+	super::validate_struct_against_table::<Matching>(&con, "test").unwrap();
+	match super::validate_struct_against_table::<Drifted>(&con, "test") {
+		Err(Error::SchemaMismatch { table, missing, extra }) => {
+			assert_eq!(table, "test");
+			assert_eq!(missing, ["name"]);
+			assert_eq!(extra, ["title"]);
+		}
+		res => panic!("Unexpected result: {:?}", res),
+	}
+	match super::validate_struct_against_table::<Matching>(&con, "absent") {
+		Err(Error::SchemaMismatch { missing, extra, .. }) => {
+			assert_eq!(missing, ["id", "title"]);
+			assert!(extra.is_empty());
+		}
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_named_set_clause() {
+	#[derive(Serialize)]
+	struct Update {
+		f_text: String,
+		f_real: f64,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text, f_real) VALUES(1, 'old', 1.5)", [])
+		.unwrap();
+	let params = super::to_params_named(Update {
+		f_text: "new".into(),
+		f_real: 2.5,
+	})
+	.unwrap();
+	let set_clause = super::named_set_clause(&params);
+	assert_eq!(set_clause, "f_text = :f_text, f_real = :f_real");
+	con.execute(
+		&format!("UPDATE test SET {} WHERE f_integer = 1", set_clause),
+		params.to_slice().as_slice(),
+	)
+	.unwrap();
+	let res = con
+		.query_row("SELECT f_text, f_real FROM test", [], |row| {
+			Ok(super::from_row::<(String, f64)>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res, ("new".into(), 2.5));
+
+	assert_eq!(super::named_set_clause(&super::NamedParamSlice::default()), "");
+}
+
+#[test]
+fn test_array_params() {
+	let con = make_connection();
+	let res = con
+		.query_row(
+			"SELECT ?1, ?2, ?3, typeof(?1)",
+			super::to_params([1_i64, 2, 3]).unwrap(),
+			|row| Ok(super::from_row::<(i64, i64, i64, String)>(row).unwrap()),
+		)
+		.unwrap();
+	assert_eq!(res, (1, 2, 3, "integer".into()));
+
+	let res = con
+		.query_row(
+			"SELECT ?1 || ?2",
+			super::to_params(["a".to_owned(), "b".to_owned()]).unwrap(),
+			|row| Ok(super::from_row::<(String,)>(row).unwrap()),
+		)
+		.unwrap();
+	assert_eq!(res, ("ab".into(),));
+
+	// top-level u8 array is a tuple of INTEGERs
+	let bytes = [1_u8, 2, 3, 4];
+	let params = super::to_value_vec(bytes).unwrap();
+	assert_eq!(params, (1..=4).map(Value::Integer).collect::<Vec<_>>());
+	// wrapped bytes are a single BLOB
+	let params = super::to_value_vec((serde_bytes::Bytes::new(&bytes),)).unwrap();
+	assert_eq!(params, [Value::Blob(bytes.to_vec())]);
+	let params = super::to_value_vec(crate::ser::Blob(bytes)).unwrap();
+	assert_eq!(params, [Value::Blob(bytes.to_vec())]);
+	let res = con
+		.query_row(
+			"SELECT ?1, typeof(?1), ?2",
+			super::to_params((crate::ser::Blob(bytes), 5)).unwrap(),
+			|row| Ok(super::from_row::<([u8; 4], String, i64)>(row).unwrap()),
+		)
+		.unwrap();
+	assert_eq!(res, (bytes, "blob".into(), 5));
+}
+
+#[test]
+fn test_row_mapper() {
+	struct Repository<D> {
+		sql: &'static str,
+		mapper: super::RowMapper<D>,
+	}
+
+	impl<D> Repository<D> {
+		fn fetch_all(&self, con: &rusqlite::Connection) -> Vec<D> {
+			let mut stmt = con.prepare(self.sql).unwrap();
+			stmt
+				.query_and_then([], &self.mapper)
+				.unwrap()
+				.collect::<Result<_, _>>()
+				.unwrap()
+		}
+	}
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_text: String,
+	}
+
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'one'), (2, 'two')")
+		.unwrap();
+	let sql = "SELECT f_integer, f_text FROM test ORDER BY f_integer";
+	let columns = super::columns_from_statement(&con.prepare(sql).unwrap());
+	let repository = Repository {
+		sql,
+		mapper: super::row_mapper::<Test>(columns),
+	};
+	assert_eq!(
+		repository.fetch_all(&con),
+		[
+			Test {
+				f_integer: 1,
+				f_text: "one".into()
+			},
+			Test {
+				f_integer: 2,
+				f_text: "two".into()
+			},
+		]
+	);
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(3, 'three')", [])
+		.unwrap();
+	assert_eq!(repository.fetch_all(&con).len(), 3);
+}
+
+#[test]
+fn test_null_if() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		#[serde(with = "crate::null_if::minus_one")]
+		f_integer: Option<i64>,
+		#[serde(
+			serialize_with = "crate::null_if::serialize::<999, _>",
+			deserialize_with = "crate::null_if::deserialize::<999, _>"
+		)]
+		f_code: Option<i64>,
+	}
+
+	let con = make_connection_with_spec("f_integer INT, f_code INT");
+	let mut insert = con.prepare("INSERT INTO test VALUES(:f_integer, :f_code)").unwrap();
+	let mut select = con.prepare("SELECT f_integer, f_code FROM test").unwrap();
+	for (value, stored) in [
+		(
+			Test {
+				f_integer: None,
+				f_code: None,
+			},
+			(-1, 999),
+		),
+		(
+			Test {
+				f_integer: Some(5),
+				f_code: Some(0),
+			},
+			(5, 0),
+		),
+	] {
+		con.execute("DELETE FROM test", []).unwrap();
+		insert
+			.execute(super::to_params_named(&value).unwrap().to_slice().as_slice())
+			.unwrap();
+		assert_eq!(
+			select
+				.query_row([], |row| Ok(super::from_row::<(i64, i64)>(row).unwrap()))
+				.unwrap(),
+			stored
+		);
+		assert_eq!(
+			select.query_row([], |row| Ok(super::from_row::<Test>(row).unwrap())).unwrap(),
+			value
+		);
+	}
+	// NULL and Some(sentinel) are read back as None
+	con.execute("UPDATE test SET f_integer = NULL, f_code = 999", []).unwrap();
+	assert_eq!(
+		select.query_row([], |row| Ok(super::from_row::<Test>(row).unwrap())).unwrap(),
+		Test {
+			f_integer: None,
+			f_code: None
+		}
+	);
+}
+
+#[test]
+fn test_from_rows_columnar() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Columns {
+		f_integer: Vec<i64>,
+		f_real: Vec<f64>,
+		f_text: Vec<Option<String>>,
+	}
+
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_real, f_text) VALUES(1, 1.5, 'one'), (2, 2.5, NULL), (3, 3.5, 'three')")
+		.unwrap();
+	let mut stmt = con
+		.prepare("SELECT f_integer, f_real, f_text FROM test ORDER BY f_integer")
+		.unwrap();
+	let res = super::from_rows_columnar::<Columns>(stmt.query([]).unwrap()).unwrap();
+	assert_eq!(
+		res,
+		Columns {
+			f_integer: vec![1, 2, 3],
+			f_real: vec![1.5, 2.5, 3.5],
+			f_text: vec![Some("one".into()), None, Some("three".into())],
+		}
+	);
+
+	let mut stmt = con.prepare("SELECT f_integer FROM test WHERE f_integer > 5").unwrap();
+	let empty = super::from_rows_columnar::<collections::BTreeMap<String, Vec<i64>>>(stmt.query([]).unwrap()).unwrap();
+	assert_eq!(empty, [("f_integer".to_owned(), vec![])].into_iter().collect());
+
+	let mut stmt = con.prepare("SELECT 'x' AS f_integer FROM test").unwrap();
+	match super::from_rows_columnar::<collections::BTreeMap<String, Vec<i64>>>(stmt.query([]).unwrap()) {
+		Err(Error::Deserialization {
+			column: Some(column), ..
+		}) => assert_eq!(column, "f_integer"),
+		res => panic!("Unexpected result: {:?}", res),
+	}
+}
+
+#[test]
+fn test_newtype_variant() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	enum Status {
+		Active,
+		Failed(String),
+	}
+
+	let con = make_connection();
+	con.execute_batch("INSERT INTO test(f_integer, f_text) VALUES(1, 'Active'), (2, 'Failed')")
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_text FROM test ORDER BY f_integer").unwrap();
+	let res = stmt
+		.query_map([], |row| Ok(super::from_row::<(Status,)>(row).unwrap().0))
+		.unwrap()
+		.map(|res| res.unwrap())
+		.collect::<Vec<_>>();
+	// the data of the newtype variant comes from the same column as the tag
+	assert_eq!(res, [Status::Active, Status::Failed("Failed".into())]);
+
+	test_value_same("TEXT", &Status::Failed("Failed".into()));
+}
+
+#[test]
+fn test_from_row_with_options() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_real: f64,
+		f_text: Option<String>,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_real, f_text) VALUES(1, 3.0, '')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_real AS f_integer, f_text FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let deserialize = |stmt: &mut rusqlite::Statement, options| {
+		stmt
+			.query_row([], |row| Ok(super::from_row_with_options::<Test>(row, &columns, &options)))
+			.unwrap()
+	};
+
+	let res = deserialize(&mut stmt, DeserializeOptions::default());
+	assert!(matches!(res, Err(Error::Deserialization { .. })));
+
+	let options = DeserializeOptions::default()
+		.integer_from_real(true)
+		.default_missing_fields(true);
+	assert_eq!(
+		deserialize(&mut stmt, options).unwrap(),
+		Test {
+			f_integer: 3,
+			f_real: 0.,
+			f_text: Some(String::new()),
+		}
+	);
+	let res = deserialize(&mut stmt, options.strict_numeric(true));
+	assert!(matches!(res, Err(Error::TypeMismatch { .. })));
+}
+
+#[test]
+fn test_to_params_with_options() {
+	#[derive(Serialize)]
+	enum Status {
+		Active,
+		Blocked,
+	}
+	#[derive(Serialize)]
+	struct Update {
+		status: Status,
+		score: f64,
+		note: Option<String>,
+	}
+
+	let con = make_connection();
+	let options = SerializeOptions::default().enum_as_index(true).float_as_text(true);
+	let res = con
+		.query_row(
+			"SELECT ?1, typeof(?1), ?2, typeof(?2)",
+			super::to_params_with_options((Status::Blocked, 0.1), &options).unwrap(),
+			|row| Ok(super::from_row::<(i64, String, String, String)>(row).unwrap()),
+		)
+		.unwrap();
+	assert_eq!(res, (1, "integer".into(), "0.1".into(), "text".into()));
+
+	let src = Update {
+		status: Status::Active,
+		score: 2.5,
+		note: None,
+	};
+	let params = super::to_params_named_with_options(&src, &options.skip_none(true)).unwrap();
+	assert_eq!(
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+		[":status", ":score"]
+	);
+	assert_eq!(params[0].1.to_sql().unwrap(), ToSqlOutput::Owned(Value::Integer(0)));
+	assert_eq!(params[1].1.to_sql().unwrap(), ToSqlOutput::Borrowed(ValueRef::Text(b"2.5")));
+	assert_eq!(super::to_params_named(&src).unwrap().len(), 3);
+}
+
+#[test]
+fn test_transparent_newtype_row() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Inner {
+		f_integer: i64,
+		f_text: String,
+	}
+	#[derive(Deserialize, Debug, PartialEq)]
+	#[serde(transparent)]
+	struct Transparent(Inner);
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Newtype(Inner);
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Id(i64);
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, 'one')", [])
+		.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_text FROM test").unwrap();
+	let inner = || Inner {
+		f_integer: 1,
+		f_text: "one".into(),
+	};
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<Transparent>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, Transparent(inner()));
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row::<Newtype>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, Newtype(inner()));
+	// wrappers of a single value still read the first column
+	let res = stmt.query_row([], |row| Ok(super::from_row::<Id>(row).unwrap())).unwrap();
+	assert_eq!(res, Id(1));
+}
+
+#[test]
+fn test_params_named_with_prefix() {
+	#[derive(Serialize)]
+	struct Test {
+		f_integer: i64,
+		f_text: Option<String>,
+	}
+
+	let src = Test {
+		f_integer: 1,
+		f_text: None,
+	};
+	let con = make_connection();
+	for (prefix, sql) in [
+		('@', "INSERT INTO test(f_integer, f_text) VALUES(@f_integer, @f_text)"),
+		('$', "INSERT INTO test(f_integer, f_text) VALUES($f_integer, $f_text)"),
+		(':', "INSERT INTO test(f_integer, f_text) VALUES(:f_integer, :f_text)"),
+	] {
+		let params = super::to_params_named_with_prefix(&src, prefix).unwrap();
+		assert_eq!(params[0].0, format!("{}f_integer", prefix));
+		con.execute(sql, params.to_slice().as_slice()).unwrap();
+	}
+	let count: i64 = con.query_row("SELECT count(*) FROM test", [], |row| row.get(0)).unwrap();
+	assert_eq!(count, 3);
+
+	assert!(matches!(
+		super::to_params_named_with_prefix(&src, '#'),
+		Err(Error::Serialization(_))
+	));
+	let options = SerializeOptions::default().named_prefix('?').skip_none(true);
+	assert!(matches!(
+		super::to_params_named_with_options(&src, &options),
+		Err(Error::Serialization(_))
+	));
+	let params = super::to_params_named_with_options(&src, &options.named_prefix('@')).unwrap();
+	assert_eq!(
+		params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+		["@f_integer"]
+	);
+}
+
+#[test]
+fn test_combined_serialize_options() {
+	#[derive(Serialize)]
+	enum Kind {
+		Small,
+		Huge,
+	}
+	#[derive(Serialize)]
+	struct Update {
+		f_integer: Option<i64>,
+		f_text: Option<String>,
+	}
+	#[derive(Serialize)]
+	struct Counter {
+		kind: Kind,
+		value: u64,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, 'one')", [])
+		.unwrap();
+	// skip_none with a custom prefix drops the missing values and names the rest with that prefix
+	let options = SerializeOptions::default().skip_none(true).named_prefix('$');
+	let src = Update {
+		f_integer: None,
+		f_text: Some("updated".into()),
+	};
+	let params = super::to_params_named_with_options(&src, &options).unwrap();
+	assert_eq!(params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["$f_text"]);
+	con.execute("UPDATE test SET f_text = $f_text", params.to_slice().as_slice())
+		.unwrap();
+	let res = con
+		.query_row("SELECT f_integer, f_text FROM test", [], |row| {
+			Ok(super::from_row::<(i64, String)>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res, (1, "updated".into()));
+
+	// enum_as_index with u64_as_text stores the variant as INTEGER and the large value as TEXT
+	let options = SerializeOptions::default().enum_as_index(true).u64_as_text(true);
+	for (src, expected) in [
+		(
+			Counter {
+				kind: Kind::Small,
+				value: 5,
+			},
+			(0, "integer".to_owned(), 5, "integer".to_owned()),
+		),
+		(
+			Counter {
+				kind: Kind::Huge,
+				value: u64::MAX,
+			},
+			(1, "integer".to_owned(), u64::MAX, "text".to_owned()),
+		),
+	] {
+		let res = con
+			.query_row(
+				"SELECT :kind, typeof(:kind), :value, typeof(:value)",
+				super::to_params_named_with_options(&src, &options)
+					.unwrap()
+					.to_slice()
+					.as_slice(),
+				|row| Ok(super::from_row::<(i64, String, u64, String)>(row).unwrap()),
+			)
+			.unwrap();
+		assert_eq!(res, expected);
+	}
+}
+
+#[test]
+fn test_params_named_from_positional() {
+	#[derive(Serialize)]
+	struct TupleStruct(i64, String);
+
+	let expected = "named params require a struct or map; use to_params for tuples/sequences";
+	for res in [
+		super::to_params_named((1, "a")),
+		super::to_params_named(vec![1, 2]),
+		super::to_params_named(TupleStruct(1, "a".into())),
+	] {
+		match res {
+			Err(e @ Error::Unsupported(_)) => assert!(e.to_string().ends_with(expected), "{}", e),
+			res => panic!("Unexpected result: {:?}", res.map(|params| params.len())),
+		}
+	}
+}
+
+#[test]
+fn test_raw_row() {
+	use crate::de::RawRow;
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Audit {
+		f_integer: i64,
+		f_text: String,
+		raw: RawRow,
+	}
+
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_text, f_real, f_blob) VALUES(1, 'one', 2.5, x'0102')",
+		[],
+	)
+	.unwrap();
+	let res = con
+		.query_row("SELECT f_integer, f_text, f_real, f_blob, NULL AS raw FROM test", [], |row| {
+			Ok(super::from_row::<Audit>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.f_integer, 1);
+	assert_eq!(res.f_text, "one");
+	let values = [
+		Value::Integer(1),
+		Value::Text("one".into()),
+		Value::Real(2.5),
+		Value::Blob(vec![1, 2]),
+	];
+	assert_eq!(res.raw.values().unwrap(), values);
+	assert_eq!(res.raw, RawRow::encode(&values));
+	// the whole row is captured when it's deserialized into RawRow directly
+	let res = con
+		.query_row("SELECT f_integer, f_text FROM test", [], |row| {
+			Ok(super::from_row::<RawRow>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.values().unwrap(), values[..2]);
+
+	let row = ["f_integer", "f_text", "raw"]
+		.into_iter()
+		.map(str::to_owned)
+		.zip(values.iter().cloned())
+		.collect::<Vec<_>>();
+	let res = super::from_value_row::<Audit>(&row).unwrap();
+	assert_eq!(res.raw.values().unwrap(), values[..2]);
+
+	assert!(RawRow(vec![5, 0, 0, 0, 1]).values().is_err());
+}
+
+#[test]
+fn test_params_named_bind() {
+	#[derive(Serialize)]
+	struct Row {
+		f_integer: i64,
+		f_text: String,
+	}
+
+	let con = make_connection();
+	let params = super::to_params_named(Row {
+		f_integer: 7,
+		f_text: "seven".to_owned(),
+	})
+	.unwrap();
+	let mut stmt = con
+		.prepare("INSERT INTO test(f_integer, f_text) VALUES(:f_integer, :f_text)")
+		.unwrap();
+	params.bind(&mut stmt).unwrap();
+	assert_eq!(stmt.raw_execute().unwrap(), 1);
+	let res: (i64, String) = con
+		.query_row("SELECT f_integer, f_text FROM test", [], |r| Ok((r.get(0)?, r.get(1)?)))
+		.unwrap();
+	assert_eq!(res, (7, "seven".to_owned()));
+
+	let mut stmt = con.prepare("INSERT INTO test(f_integer) VALUES(:f_integer)").unwrap();
+	match params.bind(&mut stmt).unwrap_err() {
+		Error::Rusqlite(rusqlite::Error::InvalidParameterName(name)) => assert_eq!(name, ":f_text"),
+		e => panic!("unexpected error: {e}"),
+	}
+}
+
+#[test]
+fn test_discriminant() {
+	use crate::discriminant::Discriminant;
+
+	#[derive(Clone, Copy, Debug, PartialEq)]
+	enum Code {
+		Ok = 200,
+		NotFound = 404,
+	}
+
+	impl Discriminant for Code {
+		fn discriminant(&self) -> i64 {
+			*self as i64
+		}
+
+		fn from_discriminant(discriminant: i64) -> Option<Self> {
+			match discriminant {
+				200 => Some(Self::Ok),
+				404 => Some(Self::NotFound),
+				_ => None,
+			}
+		}
+	}
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Response {
+		#[serde(with = "crate::discriminant")]
+		f_integer: Code,
+	}
+
+	let con = make_connection();
+	for code in [Code::Ok, Code::NotFound] {
+		let src = Response { f_integer: code };
+		con.execute("DELETE FROM test", []).unwrap();
+		con.execute(
+			"INSERT INTO test(f_integer) VALUES(:f_integer)",
+			super::to_params_named(&src).unwrap().to_slice().as_slice(),
+		)
+		.unwrap();
+		let stored: i64 = con.query_row("SELECT f_integer FROM test", [], |r| r.get(0)).unwrap();
+		assert_eq!(stored, code as i64);
+		let res = con
+			.query_row("SELECT f_integer FROM test", [], |row| Ok(super::from_row::<Response>(row)))
+			.unwrap()
+			.unwrap();
+		assert_eq!(res, src);
+	}
+
+	con.execute("UPDATE test SET f_integer = 500", []).unwrap();
+	let res = con
+		.query_row("SELECT f_integer FROM test", [], |row| Ok(super::from_row::<Response>(row)))
+		.unwrap();
+	assert!(res.is_err());
+}
+
+#[test]
+fn test_from_row_with_extras() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Partial {
+		f_integer: i64,
+		f_text: String,
+	}
+
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real, f_text, f_blob) VALUES(1, 2.5, 'one', x'0102')",
+		[],
+	)
+	.unwrap();
+	let mut stmt = con.prepare("SELECT f_integer, f_real, f_text, f_blob FROM test").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let (res, extras) = stmt
+		.query_row([], |row| Ok(super::from_row_with_extras::<Partial>(row, &columns)))
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		Partial {
+			f_integer: 1,
+			f_text: "one".into()
+		}
+	);
+	assert_eq!(
+		extras,
+		collections::HashMap::from([
+			("f_real".to_owned(), Value::Real(2.5)),
+			("f_blob".to_owned(), Value::Blob(vec![1, 2])),
+		])
+	);
+}
+
+#[test]
+fn test_params_named_skip_none() {
+	#[derive(Serialize)]
+	struct Patch {
+		f_integer: Option<i64>,
+		f_text: Option<String>,
+		f_real: f64,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text, f_real) VALUES(1, 'one', 1.5)", [])
+		.unwrap();
+	let params = super::to_params_named_skip_none(Patch {
+		f_integer: None,
+		f_text: Some("two".into()),
+		f_real: f64::NAN,
+	})
+	.unwrap();
+	assert_eq!(params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), [":f_text"]);
+	con.execute(
+		&format!("UPDATE test SET {}", super::named_set_clause(&params)),
+		params.to_slice().as_slice(),
+	)
+	.unwrap();
+	let res: (i64, String, f64) = con
+		.query_row("SELECT f_integer, f_text, f_real FROM test", [], |r| {
+			Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+		})
+		.unwrap();
+	assert_eq!(res, (1, "two".into(), 1.5));
+}
+
+#[test]
+fn test_params_struct_positional() {
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Test {
+		f_integer: i64,
+		f_real: f64,
+		f_text: String,
+		f_blob: serde_bytes::ByteBuf,
+		f_null: Option<i64>,
+	}
+
+	let src = Test {
+		f_integer: 1,
+		f_real: 2.5,
+		f_text: "three".into(),
+		f_blob: serde_bytes::ByteBuf::from(vec![4]),
+		f_null: None,
+	};
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real, f_text, f_blob, f_null) VALUES(?, ?, ?, ?, ?)",
+		super::to_params(&src).unwrap(),
+	)
+	.unwrap();
+	let res = con
+		.query_row("SELECT * FROM test", [], |row| Ok(super::from_row::<Test>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, src);
+
+	#[derive(Serialize)]
+	enum Variant {
+		Pair { a: i64, b: &'static str },
+	}
+	let params = Variant::Pair { a: 1, b: "b" }
+		.serialize(PositionalSliceSerializer::default())
+		.unwrap();
+	assert_eq!(params.len(), 2);
+	assert_eq!(params[1].to_sql().unwrap(), ToSqlOutput::Borrowed(ValueRef::Text(b"b")));
+}
+
+#[test]
+fn test_params_variant_tag() {
+	#[derive(Serialize)]
+	enum Event {
+		Login(i64),
+		Move(i64, i64),
+		Rename { name: &'static str },
+	}
+
+	let options = SerializeOptions::default().variant_tag(true);
+	let params = Event::Login(5)
+		.serialize(PositionalSliceSerializer::with_options(options))
+		.unwrap();
+	assert_eq!(
+		params.iter().map(|p| p.to_sql().unwrap()).collect::<Vec<_>>(),
+		[
+			ToSqlOutput::Borrowed(ValueRef::Text(b"Login")),
+			ToSqlOutput::Owned(Value::Integer(5))
+		]
+	);
+	let params = Event::Move(1, 2)
+		.serialize(PositionalSliceSerializer::with_options(options.enum_as_index(true)))
+		.unwrap();
+	assert_eq!(
+		params.iter().map(|p| p.to_sql().unwrap()).collect::<Vec<_>>(),
+		[
+			ToSqlOutput::Owned(Value::Integer(1)),
+			ToSqlOutput::Owned(Value::Integer(1)),
+			ToSqlOutput::Owned(Value::Integer(2))
+		]
+	);
+	let params = Event::Rename { name: "a" }
+		.serialize(PositionalSliceSerializer::with_options(options))
+		.unwrap();
+	assert_eq!(params.len(), 2);
+	assert_eq!(params[0].to_sql().unwrap(), ToSqlOutput::Borrowed(ValueRef::Text(b"Rename")));
+
+	let params = Event::Login(5).serialize(PositionalSliceSerializer::default()).unwrap();
+	assert_eq!(params.len(), 1);
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_half() {
+	use half::f16;
+
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Test {
+		#[serde(with = "crate::half::f16_real")]
+		real: f16,
+		#[serde(with = "crate::half::f16_blob")]
+		blob: f16,
+	}
+	let con = make_connection_with_spec(
+		"
+		real REAL CHECK(typeof(real) == 'real'),
+		blob BLOB CHECK(typeof(blob) == 'blob' AND length(blob) == 2)
+	",
+	);
+	for value in [f16::from_f32(1.5), f16::from_f32(-0.1), f16::MAX, f16::MIN_POSITIVE_SUBNORMAL] {
+		let src = Test {
+			real: value,
+			blob: value,
+		};
+		con.execute("DELETE FROM test", []).unwrap();
+		con.execute(
+			"INSERT INTO test VALUES(:real, :blob)",
+			super::to_params_named(&src).unwrap().to_slice().as_slice(),
+		)
+		.unwrap();
+		let res = con
+			.query_row("SELECT * FROM test", [], |row| Ok(super::from_row::<Test>(row).unwrap()))
+			.unwrap();
+		assert_eq!(res, src);
+	}
+
+	// values written by other means are rounded to the nearest f16
+	let res = con
+		.query_row("SELECT 0.1 AS real, x'0000' AS blob", [], |row| {
+			Ok(super::from_row::<Test>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.real, f16::from_f64(0.1));
+	let res = con
+		.query_row("SELECT 3 AS real, x'00' AS blob", [], |row| Ok(super::from_row::<Test>(row)))
+		.unwrap();
+	assert!(res.is_err());
+	let res = con
+		.query_row("SELECT 3 AS real, x'0000' AS blob", [], |row| {
+			Ok(super::from_row::<Test>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.real, f16::from_f32(3.));
+}
+
+#[test]
+fn test_params_map_positional() {
+	let src = collections::BTreeMap::from([("c", 3), ("a", 1), ("b", 2)]);
+	let con = make_connection_with_spec("a INT, b INT, c INT");
+	con.execute("INSERT INTO test(a, b, c) VALUES(?, ?, ?)", super::to_params(&src).unwrap())
+		.unwrap();
+	let res: (i64, i64, i64) = con
+		.query_row("SELECT a, b, c FROM test", [], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+		.unwrap();
+	assert_eq!(res, (1, 2, 3));
+
+	let params = collections::BTreeMap::<&str, i64>::new().serialize(PositionalSliceSerializer::with_options(
+		SerializeOptions::default().error_on_empty(true),
+	));
+	assert!(params.is_err());
+}
+
+#[test]
+fn test_serialized_param() {
+	use crate::Serialized;
+
+	#[derive(Serialize)]
+	enum Status {
+		Active,
+	}
+
+	let con = make_connection();
+	let blob = [1u8, 2, 3];
+	con.execute(
+		"INSERT INTO test(f_text, f_blob, f_integer) VALUES(?, ?, ?)",
+		rusqlite::params![Serialized(Status::Active), Serialized(serde_bytes::Bytes::new(&blob)), 42],
+	)
+	.unwrap();
+	let res: (String, Vec<u8>, i64) = con
+		.query_row("SELECT f_text, f_blob, f_integer FROM test", [], |r| {
+			Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+		})
+		.unwrap();
+	assert_eq!(res, ("Active".into(), blob.to_vec(), 42));
+
+	#[derive(Serialize)]
+	struct Pair(i32, i32);
+	match rusqlite::ToSql::to_sql(&Serialized(Pair(1, 2))).unwrap_err() {
+		rusqlite::Error::ToSqlConversionFailure(e) => assert!(e.is::<Error>()),
+		e => panic!("unexpected error: {e}"),
+	}
+}
+
+#[test]
+fn test_field_names() {
+	#[derive(Serialize)]
+	struct Inner {
+		extra: i64,
+	}
+
+	#[derive(Serialize)]
+	struct Test {
+		id: i64,
+		#[serde(rename = "title")]
+		name: String,
+		nested: Vec<Vec<String>>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		note: Option<String>,
+		#[serde(flatten)]
+		inner: Inner,
+	}
+
+	let src = Test {
+		id: 1,
+		name: "a".into(),
+		nested: vec![vec!["values are not serialized".into()]],
+		note: None,
+		inner: Inner { extra: 2 },
+	};
+	assert_eq!(super::field_names(&src).unwrap(), ["id", "title", "nested", "extra"]);
+	let map = collections::BTreeMap::from([("b", 2), ("a", 1)]);
+	assert_eq!(super::field_names(&map).unwrap(), ["a", "b"]);
+	assert_eq!(super::field_names(&Some(map)).unwrap(), ["a", "b"]);
+
+	assert!(matches!(super::field_names(&(1, 2)), Err(Error::Unsupported(_))));
+	assert!(matches!(super::field_names(&5), Err(Error::Unsupported(_))));
+	assert!(matches!(super::field_names(&vec!["a"]), Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn test_placeholders() {
+	#[derive(Serialize)]
+	struct Test {
+		id: i64,
+		name: &'static str,
+	}
+
+	let positional = |value: &dyn Fn(PositionalSliceSerializer) -> crate::Result<crate::PositionalParams>| {
+		super::positional_placeholders(&value(PositionalSliceSerializer::default()).unwrap())
+	};
+	assert_eq!(positional(&|ser| ().serialize(ser)), "(?)");
+	assert_eq!(positional(&|ser| [0; 0].serialize(ser)), "()");
+	assert_eq!(positional(&|ser| (1, "a").serialize(ser)), "(?, ?)");
+	assert_eq!(positional(&|ser| [1, 2, 3, 4].serialize(ser)), "(?, ?, ?, ?)");
+
+	let test = Test { id: 1, name: "a" };
+	assert_eq!(
+		super::named_placeholders(&super::to_params_named(&test).unwrap()),
+		"(:id, :name)"
+	);
+	assert_eq!(
+		super::named_placeholders(&super::to_params_named_with_prefix(&test, '@').unwrap()),
+		"(@id, @name)"
+	);
+	assert_eq!(
+		super::named_placeholders(&super::to_params_named_with_fields(&test, &["name"]).unwrap()),
+		"(:name)"
+	);
+	assert_eq!(
+		super::named_placeholders(&super::to_params_named(collections::BTreeMap::<&str, i64>::new()).unwrap()),
+		"()"
+	);
+}
+
+#[test]
+fn test_deserialized_column() {
+	use crate::Deserialized;
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	enum Status {
+		Active,
+		Blocked,
+	}
+
+	let con = make_connection();
+	con.execute("INSERT INTO test(f_integer, f_text) VALUES(1, 'Blocked'), (2, 'Unknown')", [])
+		.unwrap();
+	let (id, Deserialized(status)) = con
+		.query_row("SELECT f_integer, f_text FROM test WHERE f_integer = 1", [], |row| {
+			Ok((row.get::<_, i64>(0)?, row.get::<_, Deserialized<Status>>("f_text")?))
+		})
+		.unwrap();
+	assert_eq!((id, status), (1, Status::Blocked));
+	let res = con
+		.query_row("SELECT 1", [], |row| row.get::<_, Deserialized<Status>>(0))
+		.unwrap();
+	assert_eq!(res.0, Status::Blocked);
+
+	let res = con.query_row("SELECT f_text FROM test WHERE f_integer = 2", [], |row| {
+		row.get::<_, Deserialized<Status>>(0)
+	});
+	assert!(matches!(
+		res,
+		Err(rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, _))
+	));
+	let res = con.query_row("SELECT NULL", [], |row| row.get::<_, Deserialized<Option<Status>>>(0));
+	assert_eq!(res.unwrap().0, None);
+}
+
+#[test]
+fn test_text_null() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Text(#[serde(with = "crate::text_null")] Option<String>);
+
+	for (value, stored) in [(None, "NULL"), (Some("null"), "null"), (Some(""), "")] {
+		test_value_same(
+			&format!("TEXT NOT NULL CHECK(test_column == '{}')", stored),
+			&Text(value.map(str::to_owned)),
+		);
+	}
+
+	// SQL NULL is also read as None
+	let con = make_connection_with_spec("test_column TEXT");
+	let res = con
+		.query_row("SELECT NULL", [], |row| Ok(super::from_row::<Text>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, Text(None));
+}
+
+#[test]
+fn test_from_row_with_columns_ignoring() {
+	#[derive(Deserialize, Debug, PartialEq)]
+	#[serde(deny_unknown_fields)]
+	struct Strict {
+		f_integer: i64,
+		#[serde(default)]
+		f_text: Option<String>,
+	}
+
+	let con = make_connection();
+	let mut stmt = con.prepare("SELECT 'one' AS a, x'00ff' AS junk, 'three' AS c").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| {
+			Ok(super::from_row_with_columns_ignoring::<collections::BTreeMap<String, String>>(row, &columns, &["junk"]))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		collections::BTreeMap::from([("a".to_string(), "one".to_string()), ("c".to_string(), "three".to_string())])
+	);
+
+	let mut stmt = con.prepare("SELECT 1 AS f_integer, 2.5 AS f_real, 'one' AS f_text").unwrap();
+	let columns = super::columns_from_statement(&stmt);
+	let res = stmt
+		.query_row([], |row| {
+			Ok(super::from_row_with_columns_ignoring::<Strict>(
+				row,
+				&columns,
+				&["f_real", "f_text"],
+			))
+		})
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		res,
+		Strict {
+			f_integer: 1,
+			f_text: None
+		}
+	);
+	let res = stmt
+		.query_row([], |row| Ok(super::from_row_with_columns::<Strict>(row, &columns)))
+		.unwrap();
+	assert!(res.is_err());
+}
+
+#[test]
+fn test_named_param_slice_clone_debug() {
+	#[derive(Serialize)]
+	struct Row {
+		f_integer: i64,
+		f_text: String,
+		f_blob: serde_bytes::ByteBuf,
+	}
+
+	let params = super::to_params_named(Row {
+		f_integer: 1,
+		f_text: "one".into(),
+		f_blob: serde_bytes::ByteBuf::from(vec![1, 2]),
+	})
+	.unwrap();
+	let copy = params.clone();
+	drop(params);
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_text, f_blob) VALUES(:f_integer, :f_text, :f_blob)",
+		copy.to_slice().as_slice(),
+	)
+	.unwrap();
+	con.execute(
+		"INSERT INTO test(f_integer, f_text, f_blob) VALUES(:f_integer + 1, :f_text, :f_blob)",
+		copy.clone().to_slice().as_slice(),
+	)
+	.unwrap();
+	let res: Vec<(i64, String, Vec<u8>)> = con
+		.prepare("SELECT f_integer, f_text, f_blob FROM test")
+		.unwrap()
+		.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+		.unwrap()
+		.collect::<Result<_, _>>()
+		.unwrap();
+	assert_eq!(res, [(1, "one".into(), vec![1, 2]), (2, "one".into(), vec![1, 2])]);
+
+	assert_eq!(
+		format!("{:?}", copy),
+		r#"{":f_integer": Integer(1), ":f_text": Text("one"), ":f_blob": Blob([1, 2])}"#
+	);
+}
+
+#[test]
+fn test_upsert_fragment() {
+	#[derive(Serialize)]
+	struct Item {
+		f_integer: i64,
+		f_text: String,
+		f_real: f64,
+	}
+
+	let con = make_connection_with_spec("f_integer INT PRIMARY KEY, f_text TEXT, f_real REAL");
+	for (text, real) in [("one", 1.5), ("uno", 2.5)] {
+		let (params, upsert) = super::upsert_fragment(
+			Item {
+				f_integer: 1,
+				f_text: text.into(),
+				f_real: real,
+			},
+			&["f_integer"],
+		)
+		.unwrap();
+		assert_eq!(
+			upsert,
+			r#"ON CONFLICT("f_integer") DO UPDATE SET "f_text" = excluded."f_text", "f_real" = excluded."f_real""#
+		);
+		assert_eq!(
+			params.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(),
+			[":f_integer", ":f_text", ":f_real"]
+		);
+		con.execute(
+			&format!("INSERT INTO test VALUES {} {}", super::named_placeholders(&params), upsert),
+			params.to_slice().as_slice(),
+		)
+		.unwrap();
+	}
+	let res: (i64, String, f64) = con
+		.query_row("SELECT * FROM test", [], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+		.unwrap();
+	assert_eq!(res, (1, "uno".into(), 2.5));
+
+	let (_, upsert) = super::upsert_fragment(collections::BTreeMap::from([("id", 1)]), &["id"]).unwrap();
+	assert_eq!(upsert, r#"ON CONFLICT("id") DO NOTHING"#);
+	// the column names that are keywords or contain quotes are quoted
+	let (_, upsert) = super::upsert_fragment(collections::BTreeMap::from([("order", 1), ("my \"col\"", 2)]), &["order"]).unwrap();
+	assert_eq!(
+		upsert,
+		r#"ON CONFLICT("order") DO UPDATE SET "my ""col""" = excluded."my ""col""""#
+	);
+	assert!(matches!(
+		super::upsert_fragment(collections::BTreeMap::from([("id", 1)]), &["key"]),
+		Err(Error::Serialization(_))
+	));
+	assert!(matches!(
+		super::upsert_fragment(collections::BTreeMap::from([("id", 1)]), &[]),
+		Err(Error::Serialization(_))
+	));
+}
+
+#[test]
+fn test_hex() {
+	#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+	struct Hex(#[serde(with = "crate::hex")] Vec<u8>);
+
+	for (value, stored) in [(vec![], ""), (vec![0x00, 0x7f, 0xab, 0xff], "007fabff")] {
+		test_value_same(&format!("TEXT CHECK(test_column == '{}')", stored), &Hex(value));
+	}
+
+	let con = make_connection_with_spec("test_column TEXT");
+	let res = con
+		.query_row("SELECT hex(x'C0FFEE')", [], |row| Ok(super::from_row::<Hex>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, Hex(vec![0xc0, 0xff, 0xee]));
+	for (text, message) in [
+		("abc", "odd length"),
+		("0g", "invalid hex digit 'g'"),
+		("é0", "invalid hex digit"),
+	] {
+		let res = con
+			.query_row(&format!("SELECT '{}'", text), [], |row| Ok(super::from_row::<Hex>(row)))
+			.unwrap();
+		match res {
+			Err(Error::Deserialization { message: e, .. }) => assert!(e.contains(message), "{}", e),
+			res => panic!("unexpected result for {}: {:?}", text, res),
+		}
+	}
+}
+
+#[test]
+fn test_raw_value_deserialize() {
+	use crate::ser::Raw;
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Dynamic {
+		f_integer: i64,
+		f_blob: Raw,
+	}
+
+	let con = make_connection();
+	con.execute(
+		"INSERT INTO test(f_integer, f_real, f_text, f_blob) VALUES(1, 2.5, 'one', x'0102')",
+		[],
+	)
+	.unwrap();
+	let values = [
+		Value::Integer(1),
+		Value::Real(2.5),
+		Value::Text("one".into()),
+		Value::Blob(vec![1, 2]),
+		Value::Null,
+	];
+	let res = con
+		.query_row("SELECT * FROM test", [], |row| {
+			Ok(super::from_row_by_index::<collections::BTreeMap<usize, Raw>>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(
+		res.into_values().collect::<Vec<_>>(),
+		values.iter().cloned().map(Raw).collect::<Vec<_>>()
+	);
+	let res = con
+		.query_row("SELECT f_integer, f_blob FROM test", [], |row| {
+			Ok(super::from_row::<(Raw, Raw)>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res, (Raw(values[0].clone()), Raw(values[3].clone())));
+	let res = con
+		.query_row("SELECT f_text, f_blob FROM test", [], |row| {
+			Ok(super::from_row::<collections::HashMap<String, Raw>>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res["f_blob"], Raw(values[3].clone()));
+	assert_eq!(res["f_text"], Raw(values[2].clone()));
+	let res = con
+		.query_row("SELECT f_integer, f_blob FROM test", [], |row| {
+			Ok(super::from_row::<Dynamic>(row).unwrap())
+		})
+		.unwrap();
+	assert_eq!(res.f_blob, Raw(values[3].clone()));
+	let res = con
+		.query_row("SELECT f_real FROM test", [], |row| Ok(super::from_row::<Raw>(row).unwrap()))
+		.unwrap();
+	assert_eq!(res, Raw(values[1].clone()));
+}
+
+#[test]
+fn test_insert_all() {
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Item {
+		f_integer: i64,
+		f_text: Option<String>,
+	}
+
+	let con = make_connection();
+	let rows = (1..=3)
+		.map(|i| Item {
+			f_integer: i,
+			f_text: Some(format!("item {}", i)),
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		super::insert_all(&con, "test", &["f_integer", "f_text"], &rows, true).unwrap(),
+		3
+	);
+	let res = con
+		.prepare("SELECT f_integer, f_text FROM test ORDER BY f_integer")
+		.unwrap()
+		.query_and_then([], super::from_row::<Item>)
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, rows);
+
+	// the second row violates the CHECK constraint
+	let bad = [
+		collections::BTreeMap::from([("f_integer", crate::ser::Raw(Value::Integer(4)))]),
+		collections::BTreeMap::from([("f_integer", crate::ser::Raw(Value::Text("five".into())))]),
+	];
+	assert!(super::insert_all(&con, "test", &["f_integer"], &bad, true).is_err());
+	let count = |con: &rusqlite::Connection| -> i64 { con.query_row("SELECT count(*) FROM test", [], |r| r.get(0)).unwrap() };
+	assert_eq!(count(&con), 3);
+	assert!(super::insert_all(&con, "test", &["f_integer"], &bad, false).is_err());
+	assert_eq!(count(&con), 4);
+
+	// a column missing from the row is NULL even if the previous row had it
+	let con = rusqlite::Connection::open_in_memory().unwrap();
+	con.execute("CREATE TABLE partial (a INT, b TEXT)", []).unwrap();
+	let rows = [
+		collections::BTreeMap::from([
+			("a", crate::ser::Raw(Value::Integer(1))),
+			("b", crate::ser::Raw(Value::Text("first".into()))),
+		]),
+		collections::BTreeMap::from([("a", crate::ser::Raw(Value::Integer(2)))]),
+	];
+	assert_eq!(super::insert_all(&con, "partial", &["a", "b"], &rows, true).unwrap(), 2);
+	let res = con
+		.prepare("SELECT a, b FROM partial ORDER BY a")
+		.unwrap()
+		.query_and_then([], super::from_row::<(i64, Option<String>)>)
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(res, [(1, Some("first".to_owned())), (2, None)]);
+
+	assert_eq!(
+		super::insert_all(&con, "partial", &["a", "b c"], &rows, true),
+		Err(Error::Serialization(
+			r#"column name can't be used as a parameter name: "b c""#.into()
+		))
+	);
+}
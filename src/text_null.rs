@@ -0,0 +1,27 @@
+//! Helpers to store `Option<String>` using `TEXT` `"NULL"` instead of SQL `NULL` for `None`
+//!
+//! Useful for the legacy schemas that literally store the string `NULL` for the missing values. `None` is stored as
+//! `"NULL"` and both `"NULL"` and SQL `NULL` are read back as `None`, the comparison is case-sensitive. All other values
+//! are stored and read as is. Note that `Some("NULL")` can't round-trip and is read back as `None`.
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Example {
+//!    #[serde(with = "serde_rusqlite::text_null")]
+//!    comment: Option<String>,
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const NULL: &str = "NULL";
+
+pub fn serialize<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+	value.as_deref().unwrap_or(NULL).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+	Ok(Option::<String>::deserialize(deserializer)?.filter(|value| value != NULL))
+}